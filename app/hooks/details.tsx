@@ -49,15 +49,12 @@ export function useDetails(nftMint?: PublicKey): UseDetails {
       // Override the amounts since the redeemable amount is stored in the allocation
       // and does not have to match the token account balance
       const tokens: Array<TokenAccount> = [];
-      if (allocation.firstToken != null) {
-        tokens.push({ ...allocation.firstToken, amount: allocation.firstTokenAmount });
-      }
-      if (allocation.secondToken != null) {
-        tokens.push({ ...allocation.secondToken, amount: allocation.secondTokenAmount });
-      }
-      if (allocation.thirdToken != null) {
-        tokens.push({ ...allocation.thirdToken, amount: allocation.thirdTokenAmount });
-      }
+      allocation.tokens.forEach((x, i) => {
+        const tokenAccount = allocation.tokenAccounts[i];
+        if (tokenAccount != null) {
+          tokens.push({ ...tokenAccount, amount: x.amount });
+        }
+      });
       return tokens;
     }
     return null;
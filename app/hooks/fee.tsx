@@ -9,6 +9,7 @@ export interface UseFeeConfig {
   readonly feeAuthority: PublicKey | null;
   readonly feeWithdrawAuthority: PublicKey | null;
   readonly feeBps: number;
+  readonly maxTokens: number;
   readonly reload: () => void;
 }
 
@@ -16,6 +17,7 @@ export const FeeConfigContext = createContext<UseFeeConfig>({
   feeAuthority: null,
   feeWithdrawAuthority: null,
   feeBps: 0,
+  maxTokens: 0,
   reload: () => { throw new Error("No provider"); },
 });
 
@@ -36,6 +38,7 @@ export default function FeeConfigProvider(props: PropsWithChildren): ReactElemen
       feeAuthority: result?.feeAuthority ?? null,
       feeWithdrawAuthority: result?.feeWithdrawAuthority ?? null,
       feeBps: result?.feeBps ?? 0,
+      maxTokens: result?.maxTokens ?? 0,
       reload,
     };
   }, [result, reload]);
@@ -17,7 +17,7 @@ const Form = dynamic(async () => import("@/app/form"));
 
 export function useConfigureFeesButton(_ctx: TreasuryButtonContext): ButtonSpec {
   const { publicKey } = useWallet();
-  const { feeAuthority, feeWithdrawAuthority, feeBps } = useFeeConfig();
+  const { feeAuthority, feeWithdrawAuthority, feeBps, maxTokens } = useFeeConfig();
   const { logEvent } = useAnalytics();
   const { openPopup } = usePopup();
   const { sendTransaction } = useTransaction();
@@ -30,10 +30,12 @@ export function useConfigureFeesButton(_ctx: TreasuryButtonContext): ButtonSpec
     logEvent("configure.completed");
     const newWithdrawAuthority = fields[1].value as PublicKey;
     const newFeeBps = fields[2].value as number;
+    const newMaxTokens = fields[3].value as number;
     const instruction = createInitializeFeeInstruction({
       payer: publicKey,
       feeWithdrawAuthority: newWithdrawAuthority,
       feeBps: newFeeBps,
+      maxTokens: newMaxTokens,
     });
     const hash = await sendTransaction([instruction]);
     reload();
@@ -45,6 +47,7 @@ export function useConfigureFeesButton(_ctx: TreasuryButtonContext): ButtonSpec
       { type: "info", title: "Fee Authority", value: shortAddress(feeAuthority ?? PublicKey.default) },
       { type: "pubkey", title: "Fee Withdraw Authority", value: feeWithdrawAuthority ?? undefined, placeholder: PublicKey.default, required: true },
       { type: "number", title: "Fee Basis Points", value: feeBps, placeholder: 100, min: 0, max: 10000, suffix: "bps", required: true },
+      { type: "number", title: "Max Tokens", value: maxTokens, placeholder: 5, min: 1, max: 10, suffix: "tokens", required: true },
     ];
     logEvent("configure.opened");
     openPopup(
@@ -56,7 +59,7 @@ export function useConfigureFeesButton(_ctx: TreasuryButtonContext): ButtonSpec
         onComplete={formCompletion}
       />,
     );
-  }, [feeAuthority, feeWithdrawAuthority, feeBps, openPopup, formCompletion, logEvent]);
+  }, [feeAuthority, feeWithdrawAuthority, feeBps, maxTokens, openPopup, formCompletion, logEvent]);
 
   const enabled = publicKey == null || publicKey.equals(feeAuthority ?? PublicKey.default);
 
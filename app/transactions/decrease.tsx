@@ -9,9 +9,8 @@ import type { Mint } from "@solana/spl-token";
 import { createDecreaseAllocationInstruction } from "@/core/instruction";
 import type { FormFieldMeta } from "@/app/form/field";
 import { useTransaction } from "@/app/hooks/transaction";
-import { shortAddress } from "@/core/address";
+import { shortAddress, solMint } from "@/core/address";
 import { useTokenMetadata } from "@/app/hooks/meta";
-import { nonNull } from "@/core/array";
 import { useAnalytics } from "@/app/hooks/analytics";
 
 const Connect = dynamic(async () => import("@/app/components/connect"));
@@ -26,37 +25,26 @@ export function useDecreaseAllocationButton(ctx: AllocationButtonContext): Butto
 
   const amountMap = useMemo(() => {
     const map = new Map<string, bigint>();
-    if (allocation?.firstToken) {
-      map.set(allocation.firstTokenMint.toBase58(), allocation.firstTokenAmount);
-    }
-    if (allocation?.secondToken) {
-      map.set(allocation.secondTokenMint.toBase58(), allocation.secondTokenAmount);
-    }
-    if (allocation?.thirdToken) {
-      map.set(allocation.thirdTokenMint.toBase58(), allocation.thirdTokenAmount);
-    }
+    allocation?.tokens.forEach((x, i) => {
+      if (allocation.tokenAccounts[i] != null) {
+        map.set(x.mint.toBase58(), x.amount);
+      }
+    });
     return map;
   }, [allocation]);
 
   const tokenMap = useMemo(() => {
     const map = new Map<string, Mint>();
-    if (allocation?.firstToken) {
-      map.set(allocation.firstTokenMint.toBase58(), allocation.firstToken);
-    }
-    if (allocation?.secondToken) {
-      map.set(allocation.secondTokenMint.toBase58(), allocation.secondToken);
-    }
-    if (allocation?.thirdToken) {
-      map.set(allocation.thirdTokenMint.toBase58(), allocation.thirdToken);
-    }
+    allocation?.tokens.forEach((x, i) => {
+      const tokenAccount = allocation.tokenAccounts[i];
+      if (tokenAccount != null) {
+        map.set(x.mint.toBase58(), tokenAccount);
+      }
+    });
     return map;
   }, [allocation]);
 
-  const tokenMints = useMemo(() => [
-    allocation?.firstTokenMint,
-    allocation?.secondTokenMint,
-    allocation?.thirdTokenMint,
-  ].filter(nonNull), [tokenMap]);
+  const tokenMints = useMemo(() => allocation?.tokens.map(x => x.mint) ?? [], [allocation]);
 
   const { symbol } = useTokenMetadata(tokenMints);
 
@@ -67,11 +55,14 @@ export function useDecreaseAllocationButton(ctx: AllocationButtonContext): Butto
     logEvent("decrease.completed");
     const tokenMint = fields[0].value as string;
     const amount = fields[1].value as bigint;
+    const tokenProgram = allocation.tokens.find(x => x.mint.toBase58() === tokenMint)?.tokenProgram;
     const instruction = createDecreaseAllocationInstruction({
       payer: publicKey,
       nftMint: allocation.address,
       tokenMint: new PublicKey(tokenMint),
       amount,
+      tokenProgram,
+      unwrap: tokenMint === solMint.toBase58(),
     });
     const hash = await sendTransaction([instruction]);
     reload();
@@ -9,8 +9,7 @@ import type { Mint } from "@solana/spl-token";
 import type { FormFieldMeta } from "@/app/form/field";
 import { createExerciseAllocationInstruction } from "@/core/instruction";
 import { useTransaction } from "@/app/hooks/transaction";
-import { shortAddress } from "@/core/address";
-import { nonNull } from "@/core/array";
+import { shortAddress, solMint } from "@/core/address";
 import { useTokenMetadata } from "@/app/hooks/meta";
 import { useAnalytics } from "@/app/hooks/analytics";
 
@@ -35,37 +34,26 @@ export function useExerciseAllocationButton(ctx: AllocationButtonContext): Butto
 
   const tokenMap = useMemo(() => {
     const map = new Map<string, Mint>();
-    if (allocation?.firstToken) {
-      map.set(allocation.firstTokenMint.toBase58(), allocation.firstToken);
-    }
-    if (allocation?.secondToken) {
-      map.set(allocation.secondTokenMint.toBase58(), allocation.secondToken);
-    }
-    if (allocation?.thirdToken) {
-      map.set(allocation.thirdTokenMint.toBase58(), allocation.thirdToken);
-    }
+    allocation?.tokens.forEach((x, i) => {
+      const tokenAccount = allocation.tokenAccounts[i];
+      if (tokenAccount != null) {
+        map.set(x.mint.toBase58(), tokenAccount);
+      }
+    });
     return map;
   }, [allocation]);
 
   const amountMap = useMemo(() => {
     const map = new Map<string, bigint>();
-    if (allocation?.firstToken) {
-      map.set(allocation.firstTokenMint.toBase58(), allocation.firstTokenAmount);
-    }
-    if (allocation?.secondToken) {
-      map.set(allocation.secondTokenMint.toBase58(), allocation.secondTokenAmount);
-    }
-    if (allocation?.thirdToken) {
-      map.set(allocation.thirdTokenMint.toBase58(), allocation.thirdTokenAmount);
-    }
+    allocation?.tokens.forEach((x, i) => {
+      if (allocation.tokenAccounts[i] != null) {
+        map.set(x.mint.toBase58(), x.amount);
+      }
+    });
     return map;
   }, [allocation]);
 
-  const tokenMints = useMemo(() => [
-    allocation?.firstTokenMint,
-    allocation?.secondTokenMint,
-    allocation?.thirdTokenMint,
-  ].filter(nonNull), [tokenMap]);
+  const tokenMints = useMemo(() => allocation?.tokens.map(x => x.mint) ?? [], [allocation]);
 
   const { symbol } = useTokenMetadata(tokenMints);
 
@@ -77,9 +65,8 @@ export function useExerciseAllocationButton(ctx: AllocationButtonContext): Butto
     const instruction = createExerciseAllocationInstruction({
       payer: publicKey,
       nftMint: allocation.address,
-      firstTokenMint: allocation.firstTokenMint,
-      secondTokenMint: allocation.secondTokenMint,
-      thirdTokenMint: allocation.thirdTokenMint,
+      tokens: allocation.tokens,
+      unwrap: allocation.tokens.some(x => x.mint.equals(solMint)),
     });
     const hash = await sendTransaction([instruction]);
     // FIXME: page will no longer exist? cause allocation does not exist
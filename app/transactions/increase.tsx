@@ -11,8 +11,8 @@ import { useTransaction } from "@/app/hooks/transaction";
 import { useFeeConfig } from "@/app/hooks/fee";
 import { useInterval } from "@/app/hooks/interval";
 import { getFungibleTokenAccountsForOwner } from "@/core/token";
+import { getAllowedMints } from "@/core/allowlist";
 import { useConnection } from "@/app/hooks/connection";
-import { nonNull } from "@/core/array";
 import { useTokenMetadata } from "@/app/hooks/meta";
 import { shortAddress } from "@/core/address";
 import { useAnalytics } from "@/app/hooks/analytics";
@@ -26,33 +26,41 @@ export function useIncreaseAllocationButton(ctx: AllocationButtonContext): Butto
   const { connection } = useConnection();
   const { openPopup } = usePopup();
   const { logEvent } = useAnalytics();
-  const { feeBps } = useFeeConfig();
+  const { feeBps, maxTokens } = useFeeConfig();
   const { sendTransaction } = useTransaction();
 
   const allocationTokens = useMemo(() => {
-    return new Set([
-      allocation?.firstTokenMint.toBase58(),
-      allocation?.secondTokenMint.toBase58(),
-      allocation?.thirdTokenMint.toBase58(),
-    ].filter(nonNull));
+    return new Set(allocation?.tokens.map(x => x.mint.toBase58()));
   }, [allocation]);
 
+  const allocationFull = useMemo(() => {
+    if (allocation == null) {
+      return false;
+    }
+    return allocation.tokens.length >= maxTokens && allocation.tokens.every(x => x.amount > 0n);
+  }, [allocation, maxTokens]);
+
   const { result: tokenAccounts } = useInterval({
     interval: 30, // 30 seconds
     callback: async () => {
       if (publicKey == null) {
         return [];
       }
-      return getFungibleTokenAccountsForOwner(connection, publicKey);
+      const [accounts, allowedMints] = await Promise.all([
+        getFungibleTokenAccountsForOwner(connection, publicKey),
+        getAllowedMints(connection),
+      ]);
+      const allowed = new Set(allowedMints.map(x => x.mint.toBase58()));
+      return accounts.filter(x => allowed.has(x.mint.toBase58()));
     },
   }, [publicKey, connection]);
 
   const selectableTokens = useMemo(() => {
-    if (allocationTokens.size === 3) {
+    if (allocationFull) {
       return tokenAccounts?.filter(x => allocationTokens.has(x.mint.toBase58())) ?? [];
     }
     return tokenAccounts ?? [];
-  }, [tokenAccounts, allocationTokens]);
+  }, [tokenAccounts, allocationTokens, allocationFull]);
 
   const tokenMap = useMemo(() => {
     return new Map(selectableTokens.map(x => [x.mint.toBase58(), x]));
@@ -32,12 +32,13 @@ export function useInitializeAllocationButton(): Omit<ButtonSpec, "disabled"> {
     const decreaseAuthority = fields[0].value as PublicKey;
     const metadataUri = fields[1].value as string;
     const keypair = Keypair.generate();
-    const instructions = await createNftMintInstructions(connection, keypair, publicKey, metadataUri);
+    const { instructions, metadata } = await createNftMintInstructions(connection, keypair, publicKey, metadataUri);
     instructions.push(
       createInitializeAllocationInstruction({
         payer: publicKey,
         nftMint: keypair.publicKey,
         authority: decreaseAuthority,
+        metadata,
       }),
     );
     const hash = await sendTransaction(instructions, [keypair]);
@@ -2,8 +2,9 @@ import { feeConfigAddress } from "@/core/address";
 import { linkAccount, linkTransaction } from "@/core/ansi";
 import { rpcUrl, signer } from "@/core/env";
 import { createInitializeFeeInstruction } from "@/core/instruction";
+import { promptText } from "@/core/prompt";
 import { sendAndConfirmTransaction } from "@/core/transaction";
-import { Connection } from "@solana/web3.js";
+import { Connection, PublicKey } from "@solana/web3.js";
 
 export default async function initializeFeeConfig(): Promise<void> {
   const connection = new Connection(rpcUrl);
@@ -11,8 +12,15 @@ export default async function initializeFeeConfig(): Promise<void> {
   const feeConfigInfo = await connection.getAccountInfo(feeConfigAddress);
   const feeConfigInitialized = feeConfigInfo != null;
 
+  const defaultMintsString = await promptText("What are the default mints for new allocations (comma separated, empty for none)?");
+  const defaultMints = defaultMintsString.split(",")
+    .map(x => x.trim())
+    .filter(x => x.length > 0)
+    .map(x => new PublicKey(x));
+
   const instructions = [createInitializeFeeInstruction({
     payer: signer.publicKey,
+    defaultMints: feeConfigInitialized && defaultMints.length === 0 ? undefined : defaultMints,
   })];
 
   const signature = await sendAndConfirmTransaction({
@@ -10,7 +10,7 @@ export default async function createNonFungibleToken(): Promise<void> {
   const keypair = Keypair.generate();
   const uri = await promptText("What metadata uri?", "https://arweave.net/NiFmd5Nm15DkEaLC-ltEZlYmFoyf1_2h3VN2u2Vsn9E");
 
-  const instructions = await createNftMintInstructions(connection, keypair, signer.publicKey, uri);
+  const { instructions } = await createNftMintInstructions(connection, keypair, signer.publicKey, uri);
 
   const signature = await sendAndConfirmTransaction({
     connection,
@@ -19,12 +19,35 @@ export function associatedTokenAddress(owner: PublicKey, mint: PublicKey, progra
 
 export const jewlProgramId = new PublicKey(idl.address);
 export const feeConfigAddress = PublicKey.findProgramAddressSync([Buffer.from("fee")], jewlProgramId)[0];
+export const eventAuthorityAddress = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], jewlProgramId)[0];
 
 export function allocationAddress(nftMint: PublicKey): PublicKey {
   const seeds = [Buffer.from("allocation"), nftMint.toBuffer()];
   return PublicKey.findProgramAddressSync(seeds, jewlProgramId)[0];
 }
 
+export function allocationUnwrapAddress(nftMint: PublicKey): PublicKey {
+  const seeds = [Buffer.from("allocation_unwrap"), nftMint.toBuffer()];
+  return PublicKey.findProgramAddressSync(seeds, jewlProgramId)[0];
+}
+
+export function allocationMintAddress(creator: PublicKey, nonce: bigint): PublicKey {
+  const nonceBuffer = Buffer.alloc(8);
+  nonceBuffer.writeBigUInt64LE(nonce);
+  const seeds = [Buffer.from("allocation_mint"), creator.toBuffer(), nonceBuffer];
+  return PublicKey.findProgramAddressSync(seeds, jewlProgramId)[0];
+}
+
+export function allowedMintAddress(mint: PublicKey): PublicKey {
+  const seeds = [Buffer.from("allowed_mint"), mint.toBuffer()];
+  return PublicKey.findProgramAddressSync(seeds, jewlProgramId)[0];
+}
+
+export function claimAddress(nftMint: PublicKey): PublicKey {
+  const seeds = [Buffer.from("claim"), nftMint.toBuffer()];
+  return PublicKey.findProgramAddressSync(seeds, jewlProgramId)[0];
+}
+
 export function metadataAddress(mint: PublicKey): PublicKey {
   const seeds = [Buffer.from("metadata"), metadataProgramId.toBuffer(), mint.toBuffer()];
   return PublicKey.findProgramAddressSync(seeds, metadataProgramId)[0];
@@ -7,9 +7,7 @@ import { getTokenAccounts, getNonFungibleTokenAccountsForOwner, getTokenAccount
 import { getAccountsBatched } from "@/core/account";
 
 export type Allocation = AllocationAccount & TokenAccount & {
-  firstToken: TokenAccount | null;
-  secondToken: TokenAccount | null;
-  thirdToken: TokenAccount | null;
+  tokenAccounts: Array<TokenAccount | null>;
 };
 
 export async function getAllocation(connection: Connection, nftMint: PublicKey): Promise<Allocation | null> {
@@ -21,13 +19,10 @@ export async function getAllocation(connection: Connection, nftMint: PublicKey):
   if (tokenAccount == null) { return null; }
   if (allocationInfo == null) { return null; }
   const allocation = unpackAllocation(address, allocationInfo);
-  const tokenAddresses = [
-    associatedTokenAddress(allocation.firstTokenMint, allocation.address),
-    associatedTokenAddress(allocation.secondTokenMint, allocation.address),
-    associatedTokenAddress(allocation.thirdTokenMint, allocation.address),
-  ];
-  const [firstToken, secondToken, thirdToken] = await getTokenAccounts(connection, tokenAddresses);
-  return { ...tokenAccount, ...allocation, firstToken, secondToken, thirdToken };
+  const tokenAddresses = allocation.tokens.map(x => associatedTokenAddress(allocation.address, x.mint, x.tokenProgram));
+  const tokenPrograms = allocation.tokens.map(x => x.tokenProgram);
+  const tokenAccounts = await getTokenAccounts(connection, tokenAddresses, tokenPrograms);
+  return { ...tokenAccount, ...allocation, tokenAccounts };
 }
 
 export async function getAllocations(connection: Connection, walletAddress: PublicKey): Promise<Array<Allocation>> {
@@ -41,16 +36,15 @@ export async function getAllocations(connection: Connection, walletAddress: Publ
     const allocation = unpackAllocation(allocationAddresses[i], allocationInfo);
     allocationsWithoutTokens.push({ ...tokenAccounts[i], ...allocation });
   }
-  const tokenAddresses = allocationsWithoutTokens.flatMap(x => [
-    associatedTokenAddress(x.firstTokenMint, x.address),
-    associatedTokenAddress(x.secondTokenMint, x.address),
-    associatedTokenAddress(x.thirdTokenMint, x.address),
-  ]);
-  const tokens = await getTokenAccounts(connection, tokenAddresses);
+  const tokenAddresses = allocationsWithoutTokens.flatMap(x => x.tokens.map(y => associatedTokenAddress(x.address, y.mint, y.tokenProgram)));
+  const tokenPrograms = allocationsWithoutTokens.flatMap(x => x.tokens.map(y => y.tokenProgram));
+  const tokens = await getTokenAccounts(connection, tokenAddresses, tokenPrograms);
   const allocations: Array<Allocation> = [];
-  for (let i = 0; i < tokens.length; i += 3) {
-    const [firstToken, secondToken, thirdToken] = tokens.slice(i, i + 3);
-    allocations.push({ ...allocationsWithoutTokens[i / 3], firstToken, secondToken, thirdToken });
+  let offset = 0;
+  for (const allocation of allocationsWithoutTokens) {
+    const tokenAccounts = tokens.slice(offset, offset + allocation.tokens.length);
+    allocations.push({ ...allocation, tokenAccounts });
+    offset += allocation.tokens.length;
   }
   return allocations;
 }
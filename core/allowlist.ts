@@ -0,0 +1,13 @@
+import type { Connection } from "@solana/web3.js";
+import { jewlProgramId } from "@/core/address";
+import type { AllowedMintAccount } from "@/core/coder";
+import { allowedMintFilter, unpackAllowedMint } from "@/core/coder";
+
+export async function getAllowedMints(connection: Connection): Promise<Array<AllowedMintAccount>> {
+  const accounts = await connection.getProgramAccounts(jewlProgramId, {
+    filters: [{ memcmp: allowedMintFilter() }],
+  });
+  return accounts
+    .map(x => unpackAllowedMint(x.pubkey, x.account))
+    .filter(x => x.initialized);
+}
@@ -1,35 +1,68 @@
 import type { Jewl } from "@/target/types/jewl";
-import type { Idl, IdlAccounts } from "@coral-xyz/anchor";
-import { BN, BorshAccountsCoder } from "@coral-xyz/anchor";
+import type { Idl, IdlAccounts, IdlEvents, IdlTypes } from "@coral-xyz/anchor";
+import { BN, BorshAccountsCoder, BorshEventCoder } from "@coral-xyz/anchor";
 import type { PublicKey, AccountInfo } from "@solana/web3.js";
 import idl from "@/target/idl/jewl.json";
 import { jewlProgramId } from "@/core/address";
 import { convertIdlToCamelCase } from "@coral-xyz/anchor/dist/cjs/idl";
 
 const coder = new BorshAccountsCoder(convertIdlToCamelCase(idl as Idl));
+const eventCoder = new BorshEventCoder(convertIdlToCamelCase(idl as Idl));
+
+// Events emitted with emit_cpi are a self invocation whose data starts with this tag
+const eventInstructionTag = Buffer.from([0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d]);
 
 export type WithAddress<T> = {
   readonly address: PublicKey;
 } & T;
 
-type AsBigInt<T> = { [K in keyof T]: T[K] extends BN ? bigint : T[K] };
-type AsBN<T> = { [K in keyof T]: T[K] extends bigint ? BN : T[K] };
+type AsBigInt<T> = T extends BN ? bigint : T extends PublicKey ? T : T extends Array<infer U> ? Array<AsBigInt<U>> : T extends object ? { [K in keyof T]: AsBigInt<T[K]> } : T;
+type AsBN<T> = T extends bigint ? BN : T extends PublicKey ? T : T extends Array<infer U> ? Array<AsBN<U>> : T extends object ? { [K in keyof T]: AsBN<T[K]> } : T;
 
 type BorshFeeConfigAccount = IdlAccounts<Jewl>["feeConfigAccount"];
 type BorshAllocationAccount = IdlAccounts<Jewl>["allocationAccount"];
+type BorshAllowedMintAccount = IdlAccounts<Jewl>["allowedMintAccount"];
+type BorshVestingSchedule = IdlTypes<Jewl>["vestingSchedule"];
+type BorshAllocationMetadata = IdlTypes<Jewl>["allocationMetadata"];
+type BorshEvents = IdlEvents<Jewl>;
 
 export type FeeConfigAccount = AsBigInt<BorshFeeConfigAccount>;
 export type AllocationAccount = AsBigInt<BorshAllocationAccount>;
+export type AllowedMintAccount = AsBigInt<BorshAllowedMintAccount>;
+export type VestingSchedule = AsBigInt<BorshVestingSchedule>;
+export type AllocationMetadata = AsBigInt<BorshAllocationMetadata>;
+export type JewlEvent = { [K in keyof BorshEvents]: { name: K, data: AsBigInt<BorshEvents[K]> } }[keyof BorshEvents];
+
+function isPlainObject(value: unknown): value is object {
+  return typeof value === "object" && value !== null && Object.getPrototypeOf(value) === Object.prototype;
+}
+
+function toBigInt(value: unknown): unknown {
+  if (value instanceof BN) {
+    return BigInt(value.toString());
+  } else if (Array.isArray(value)) {
+    return value.map(toBigInt);
+  } else if (isPlainObject(value)) {
+    return convertToBigInt(value);
+  }
+  return value;
+}
+
+function fromBigInt(value: unknown): unknown {
+  if (typeof value === "bigint") {
+    return new BN(value.toString());
+  } else if (Array.isArray(value)) {
+    return value.map(fromBigInt);
+  } else if (isPlainObject(value)) {
+    return convertFromBigInt(value);
+  }
+  return value;
+}
 
 export function convertToBigInt<T extends object>(object: T): AsBigInt<T> {
   const result: Record<string, unknown> = {};
   for (const key in object) {
-    const value = object[key] as unknown;
-    if (value instanceof BN) {
-      result[key] = BigInt(value.toString());
-    } else {
-      result[key] = value;
-    }
+    result[key] = toBigInt(object[key]);
   }
   return result as AsBigInt<T>;
 }
@@ -37,12 +70,7 @@ export function convertToBigInt<T extends object>(object: T): AsBigInt<T> {
 export function convertFromBigInt<T extends object>(object: T): AsBN<T> {
   const result: Record<string, unknown> = {};
   for (const key in object) {
-    const value = object[key] as unknown;
-    if (typeof value === "bigint") {
-      result[key] = new BN(value.toString());
-    } else {
-      result[key] = value;
-    }
+    result[key] = fromBigInt(object[key]);
   }
   return result as AsBN<T>;
 }
@@ -65,6 +93,31 @@ export function unpackAllocation(address: PublicKey, accountInfo: AccountInfo<Bu
   return convertToBigInt({ address, ...decoded });
 }
 
+export function unpackAllowedMint(address: PublicKey, accountInfo: AccountInfo<Buffer | Uint8Array>, programId = jewlProgramId): WithAddress<AllowedMintAccount> {
+  if (!accountInfo.owner.equals(programId)) {
+    throw new Error("Invalid owner");
+  }
+  const data = Buffer.from(accountInfo.data);
+  const decoded = coder.decode<BorshAllowedMintAccount>("allowedMintAccount", data);
+  return convertToBigInt({ address, ...decoded });
+}
+
+export function unpackEvent(instructionData: Buffer | Uint8Array): JewlEvent | null {
+  const data = Buffer.from(instructionData);
+  if (!data.subarray(0, 8).equals(eventInstructionTag)) {
+    return null;
+  }
+  const decoded = eventCoder.decode(data.subarray(8).toString("base64"));
+  if (decoded == null) {
+    return null;
+  }
+  return { name: decoded.name, data: convertToBigInt(decoded.data) } as JewlEvent;
+}
+
+export function allowedMintFilter(): { offset: number, bytes: string } {
+  return coder.memcmp("allowedMintAccount");
+}
+
 export async function packFeeConfig(state: FeeConfigAccount): Promise<Buffer> {
   const stateBN = convertFromBigInt(state);
   return coder.encode("feeConfigAccount", stateBN);
@@ -74,3 +127,8 @@ export async function packAllocation(state: AllocationAccount): Promise<Buffer>
   const stateBN = convertFromBigInt(state);
   return coder.encode("allocationAccount", stateBN);
 }
+
+export async function packAllowedMint(state: AllowedMintAccount): Promise<Buffer> {
+  const stateBN = convertFromBigInt(state);
+  return coder.encode("allowedMintAccount", stateBN);
+}
@@ -1,9 +1,11 @@
 import type { TransactionInstruction } from "@solana/web3.js";
-import { Connection, PublicKey } from "@solana/web3.js";
+import { Connection, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
 import { AnchorProvider, BN, Program } from "@coral-xyz/anchor";
 import type { Jewl } from "@/target/types/jewl";
 import idl from "@/target/idl/jewl.json";
-import { allocationAddress, associatedTokenAddress, associatedTokenProgramId, feeConfigAddress, solMint, systemProgramId, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
+import type { AllocationMetadata, VestingSchedule } from "@/core/coder";
+import { convertFromBigInt } from "@/core/coder";
+import { allocationAddress, allocationMintAddress, allocationUnwrapAddress, allowedMintAddress, associatedTokenAddress, claimAddress, associatedTokenProgramId, eventAuthorityAddress, feeConfigAddress, jewlProgramId, systemProgramId, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
 
 const fauxWallet = {
   publicKey: PublicKey.default,
@@ -15,23 +17,36 @@ const fauxConnection = new Connection("https://api.devnet.solana.com");
 const fauxProvider = new AnchorProvider(fauxConnection, fauxWallet, {});
 const fauxProgram = new Program<Jewl>(idl as Jewl, fauxProvider);
 
+export interface AllocationTokenProgram {
+  mint: PublicKey;
+  tokenProgram: PublicKey;
+}
+
 export interface InitializeFeeInstructionProps {
   payer: PublicKey;
   feeBps?: number;
   feeAuthority?: PublicKey;
   feeWithdrawAuthority?: PublicKey;
+  maxTokens?: number;
+  defaultMints?: Array<PublicKey>;
 }
 
 export function createInitializeFeeInstruction(props: InitializeFeeInstructionProps): TransactionInstruction {
   const feeBps = props.feeBps ?? null;
   const feeAuthority = props.feeAuthority ?? null;
   const withdrawAuthority = props.feeWithdrawAuthority ?? null;
-  return fauxProgram.instruction.initializeFee(feeBps, feeAuthority, withdrawAuthority, {
+  const maxTokens = props.maxTokens ?? null;
+  const defaultMints = props.defaultMints ?? null;
+  const remainingAccounts = (props.defaultMints ?? []).map(mint => ({ pubkey: mint, isSigner: false, isWritable: false }));
+  return fauxProgram.instruction.initializeFee(feeBps, feeAuthority, withdrawAuthority, maxTokens, defaultMints, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
       systemProgram: systemProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
+    remainingAccounts,
   });
 }
 
@@ -40,20 +55,85 @@ export interface WithdrawFeeInstructionProps {
   tokenMint: PublicKey;
   feeTokenAccount?: PublicKey;
   amount?: bigint;
+  tokenProgram?: PublicKey;
 }
 
 export function createWithdrawFeeInstruction(props: WithdrawFeeInstructionProps): TransactionInstruction {
+  const tokenProgram = props.tokenProgram ?? tokenProgramId;
   const amount = props.amount != null ? new BN(props.amount.toString()) : null;
   return fauxProgram.instruction.withdrawFee(amount, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
       tokenMint: props.tokenMint,
-      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint),
-      feeTokenAccount: props.feeTokenAccount ?? associatedTokenAddress(feeConfigAddress, props.tokenMint),
+      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint, tokenProgram),
+      feeTokenAccount: props.feeTokenAccount ?? associatedTokenAddress(feeConfigAddress, props.tokenMint, tokenProgram),
+      systemProgram: systemProgramId,
+      tokenProgram,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface WithdrawFeesBatchInstructionProps {
+  payer: PublicKey;
+  destination?: PublicKey;
+  tokens: Array<AllocationTokenProgram & { amount?: bigint, feeTokenAccount?: PublicKey }>;
+}
+
+export function createWithdrawFeesBatchInstruction(props: WithdrawFeesBatchInstructionProps): TransactionInstruction {
+  const destination = props.destination ?? props.payer;
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram, feeTokenAccount }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: feeTokenAccount ?? associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(destination, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  const amounts = props.tokens.map(({ amount }) => amount != null ? new BN(amount.toString()) : null);
+  return fauxProgram.instruction.withdrawFeesBatch(amounts, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      destination,
       systemProgram: systemProgramId,
       tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
       associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts,
+  });
+}
+
+export interface AllowedMintInstructionProps {
+  payer: PublicKey;
+  tokenMint: PublicKey;
+}
+
+export function createAddAllowedMintInstruction(props: AllowedMintInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.addAllowedMint({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      tokenMint: props.tokenMint,
+      allowedMint: allowedMintAddress(props.tokenMint),
+      systemProgram: systemProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export function createRemoveAllowedMintInstruction(props: AllowedMintInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.removeAllowedMint({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      allowedMint: allowedMintAddress(props.tokenMint),
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
   });
 }
@@ -62,10 +142,15 @@ export interface InitializeAllocationInstructionProps {
   payer: PublicKey;
   nftMint: PublicKey;
   authority?: PublicKey;
+  vesting?: VestingSchedule;
+  expiresAt?: bigint;
+  metadata?: AllocationMetadata;
 }
 
 export function createInitializeAllocationInstruction(props: InitializeAllocationInstructionProps): TransactionInstruction {
-  return fauxProgram.instruction.initializeAllocation(props.authority ?? null, {
+  const vesting = props.vesting != null ? convertFromBigInt(props.vesting) : null;
+  const expiresAt = props.expiresAt != null ? new BN(props.expiresAt.toString()) : null;
+  return fauxProgram.instruction.initializeAllocation(props.authority ?? null, vesting, expiresAt, props.metadata ?? null, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
@@ -75,6 +160,38 @@ export function createInitializeAllocationInstruction(props: InitializeAllocatio
       systemProgram: systemProgramId,
       tokenExtensionsProgram: tokenExtensionsProgramId,
       associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface CreateAllocationInstructionProps {
+  payer: PublicKey;
+  nonce: bigint;
+  metadata: AllocationMetadata;
+  authority?: PublicKey;
+  vesting?: VestingSchedule;
+  expiresAt?: bigint;
+  soulbound?: boolean;
+}
+
+export function createCreateAllocationInstruction(props: CreateAllocationInstructionProps): TransactionInstruction {
+  const nftMint = allocationMintAddress(props.payer, props.nonce);
+  const vesting = props.vesting != null ? convertFromBigInt(props.vesting) : null;
+  const expiresAt = props.expiresAt != null ? new BN(props.expiresAt.toString()) : null;
+  return fauxProgram.instruction.createAllocation(new BN(props.nonce.toString()), props.authority ?? null, vesting, expiresAt, props.metadata, props.soulbound ?? false, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint,
+      nftToken: associatedTokenAddress(props.payer, nftMint, tokenExtensionsProgramId),
+      allocation: allocationAddress(nftMint),
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
   });
 }
@@ -84,24 +201,64 @@ export interface IncreaseAllocationInstructionProps {
   nftMint: PublicKey;
   tokenMint: PublicKey;
   amount: bigint;
+  tokenProgram?: PublicKey;
+  wrap?: boolean;
 }
 
 export function createIncreaseAllocationInstruction(props: IncreaseAllocationInstructionProps): TransactionInstruction {
+  const tokenProgram = props.tokenProgram ?? tokenProgramId;
   const allocation = allocationAddress(props.nftMint);
-  return fauxProgram.instruction.increaseAllocation(new BN(props.amount.toString()), {
+  return fauxProgram.instruction.increaseAllocation(new BN(props.amount.toString()), props.wrap ?? false, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
       nftMint: props.nftMint,
       allocation,
       tokenMint: props.tokenMint,
-      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint),
-      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint),
-      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint),
+      allowedMint: allowedMintAddress(props.tokenMint),
+      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint, tokenProgram),
+      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint, tokenProgram),
+      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint, tokenProgram),
+      systemProgram: systemProgramId,
+      tokenProgram,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface IncreaseAllocationBatchInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  tokens: Array<AllocationTokenProgram & { amount: bigint }>;
+}
+
+export function createIncreaseAllocationBatchInstruction(props: IncreaseAllocationBatchInstructionProps): TransactionInstruction {
+  const allocation = allocationAddress(props.nftMint);
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: allowedMintAddress(mint), isSigner: false, isWritable: false },
+    { pubkey: associatedTokenAddress(props.payer, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  const amounts = props.tokens.map(({ amount }) => new BN(amount.toString()));
+  return fauxProgram.instruction.increaseAllocationBatch(amounts, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      allocation,
       systemProgram: systemProgramId,
       tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
       associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
+    remainingAccounts,
   });
 }
 
@@ -110,23 +267,32 @@ export interface DecreaseAllocationInstructionProps {
   nftMint: PublicKey;
   tokenMint: PublicKey;
   amount: bigint;
+  tokenProgram?: PublicKey;
+  unwrap?: boolean;
+  recipient?: PublicKey;
 }
 
 export function createDecreaseAllocationInstruction(props: DecreaseAllocationInstructionProps): TransactionInstruction {
+  const tokenProgram = props.tokenProgram ?? tokenProgramId;
   const allocation = allocationAddress(props.nftMint);
-  return fauxProgram.instruction.decreaseAllocation(new BN(props.amount.toString()), {
+  return fauxProgram.instruction.decreaseAllocation(new BN(props.amount.toString()), props.unwrap ?? false, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
       nftMint: props.nftMint,
       allocation,
       tokenMint: props.tokenMint,
-      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint),
-      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint),
-      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint),
+      recipient: props.recipient ?? null,
+      signerTokenAccount: props.unwrap === true ? null : associatedTokenAddress(props.recipient ?? props.payer, props.tokenMint, tokenProgram),
+      unwrapTokenAccount: props.unwrap === true ? allocationUnwrapAddress(props.nftMint) : null,
+      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint, tokenProgram),
+      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint, tokenProgram),
       systemProgram: systemProgramId,
-      tokenProgram: tokenProgramId,
+      tokenProgram,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
       associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
   });
 }
@@ -134,37 +300,461 @@ export function createDecreaseAllocationInstruction(props: DecreaseAllocationIns
 export interface ExerciseAllocationInstructionProps {
   payer: PublicKey;
   nftMint: PublicKey;
-  firstTokenMint?: PublicKey;
-  secondTokenMint?: PublicKey;
-  thirdTokenMint?: PublicKey;
+  tokens: Array<AllocationTokenProgram>;
+  unwrap?: boolean;
+  recipient?: PublicKey;
 }
 
 export function createExerciseAllocationInstruction(props: ExerciseAllocationInstructionProps): TransactionInstruction {
   const allocation = allocationAddress(props.nftMint);
-  return fauxProgram.instruction.exerciseAllocation({
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: associatedTokenAddress(props.recipient ?? props.payer, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  return fauxProgram.instruction.exerciseAllocation(props.unwrap ?? false, {
     accounts: {
       signer: props.payer,
       feeConfig: feeConfigAddress,
       nftMint: props.nftMint,
       nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
       allocation: allocation,
-      firstTokenMint: props.firstTokenMint ?? solMint,
-      signerFirstTokenAccount: associatedTokenAddress(props.payer, props.firstTokenMint ?? solMint),
-      allocationFirstTokenAccount: associatedTokenAddress(allocation, props.firstTokenMint ?? solMint),
-      feeFirstTokenAccount: associatedTokenAddress(feeConfigAddress, props.firstTokenMint ?? solMint),
-      secondTokenMint: props.secondTokenMint ?? solMint,
-      signerSecondTokenAccount: associatedTokenAddress(props.payer, props.secondTokenMint ?? solMint),
-      allocationSecondTokenAccount: associatedTokenAddress(allocation, props.secondTokenMint ?? solMint),
-      feeSecondTokenAccount: associatedTokenAddress(feeConfigAddress, props.secondTokenMint ?? solMint),
-      thirdTokenMint: props.thirdTokenMint ?? solMint,
-      signerThirdTokenAccount: associatedTokenAddress(props.payer, props.thirdTokenMint ?? solMint),
-      allocationThirdTokenAccount: associatedTokenAddress(allocation, props.thirdTokenMint ?? solMint),
-      feeThirdTokenAccount: associatedTokenAddress(feeConfigAddress, props.thirdTokenMint ?? solMint),
+      recipient: props.recipient ?? null,
+      systemProgram: systemProgramId,
+      tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts,
+  });
+}
+
+export interface ExerciseAllocationBatchInstructionProps {
+  payer: PublicKey;
+  allocations: Array<{ nftMint: PublicKey, tokens: Array<AllocationTokenProgram> }>;
+  unwrap?: boolean;
+  recipient?: PublicKey;
+}
+
+export function createExerciseAllocationBatchInstruction(props: ExerciseAllocationBatchInstructionProps): TransactionInstruction {
+  // Recipient and fee token accounts of the same mint are shared between allocations in the transaction message
+  const remainingAccounts = props.allocations.flatMap(({ nftMint, tokens }) => {
+    const allocation = allocationAddress(nftMint);
+    return [
+      { pubkey: nftMint, isSigner: false, isWritable: true },
+      { pubkey: associatedTokenAddress(props.payer, nftMint, tokenExtensionsProgramId), isSigner: false, isWritable: true },
+      { pubkey: allocation, isSigner: false, isWritable: true },
+      ...tokens.flatMap(({ mint, tokenProgram }) => [
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: associatedTokenAddress(props.recipient ?? props.payer, mint, tokenProgram), isSigner: false, isWritable: true },
+        { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+        { pubkey: associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+      ]),
+    ];
+  });
+  return fauxProgram.instruction.exerciseAllocationBatch(props.unwrap ?? false, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      recipient: props.recipient ?? null,
+      systemProgram: systemProgramId,
+      tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts,
+  });
+}
+
+export interface SplitAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  nonce: bigint;
+  amounts: Array<bigint>;
+  metadata: AllocationMetadata;
+  tokens: Array<AllocationTokenProgram>;
+}
+
+export function createSplitAllocationInstruction(props: SplitAllocationInstructionProps): TransactionInstruction {
+  const allocation = allocationAddress(props.nftMint);
+  const newNftMint = allocationMintAddress(props.payer, props.nonce);
+  const newAllocation = allocationAddress(newNftMint);
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(newAllocation, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  const amounts = props.amounts.map(amount => new BN(amount.toString()));
+  return fauxProgram.instruction.splitAllocation(new BN(props.nonce.toString()), amounts, props.metadata, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      allocation,
+      newNftMint,
+      newNftToken: associatedTokenAddress(props.payer, newNftMint, tokenExtensionsProgramId),
+      newAllocation,
+      systemProgram: systemProgramId,
+      tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts,
+  });
+}
+
+export interface MergeAllocationsInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  sourceNftMint: PublicKey;
+  tokens: Array<AllocationTokenProgram>;
+  authorities?: Array<PublicKey>;
+}
+
+export function createMergeAllocationsInstruction(props: MergeAllocationsInstructionProps): TransactionInstruction {
+  const allocation = allocationAddress(props.nftMint);
+  const sourceAllocation = allocationAddress(props.sourceNftMint);
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: associatedTokenAddress(sourceAllocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  const authorityAccounts = (props.authorities ?? []).map(pubkey => ({ pubkey, isSigner: true, isWritable: false }));
+  return fauxProgram.instruction.mergeAllocations({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      allocation,
+      sourceNftMint: props.sourceNftMint,
+      sourceNftToken: associatedTokenAddress(props.payer, props.sourceNftMint, tokenExtensionsProgramId),
+      sourceAllocation,
       systemProgram: systemProgramId,
       tokenProgram: tokenProgramId,
       tokenExtensionsProgram: tokenExtensionsProgramId,
       associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts: remainingAccounts.concat(authorityAccounts),
+  });
+}
+
+export interface WithdrawFromAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  tokenMint: PublicKey;
+  amount: bigint;
+  tokenProgram?: PublicKey;
+}
+
+export function createWithdrawFromAllocationInstruction(props: WithdrawFromAllocationInstructionProps): TransactionInstruction {
+  const tokenProgram = props.tokenProgram ?? tokenProgramId;
+  const allocation = allocationAddress(props.nftMint);
+  return fauxProgram.instruction.withdrawFromAllocation(new BN(props.amount.toString()), {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      allocation,
+      tokenMint: props.tokenMint,
+      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint, tokenProgram),
+      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint, tokenProgram),
+      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint, tokenProgram),
+      systemProgram: systemProgramId,
+      tokenProgram,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
     },
   });
 }
 
+export interface ClaimVestedInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  tokenMint: PublicKey;
+  tokenProgram?: PublicKey;
+}
+
+export function createClaimVestedInstruction(props: ClaimVestedInstructionProps): TransactionInstruction {
+  const tokenProgram = props.tokenProgram ?? tokenProgramId;
+  const allocation = allocationAddress(props.nftMint);
+  return fauxProgram.instruction.claimVested({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      allocation,
+      tokenMint: props.tokenMint,
+      signerTokenAccount: associatedTokenAddress(props.payer, props.tokenMint, tokenProgram),
+      allocationTokenAccount: associatedTokenAddress(allocation, props.tokenMint, tokenProgram),
+      feeTokenAccount: associatedTokenAddress(feeConfigAddress, props.tokenMint, tokenProgram),
+      systemProgram: systemProgramId,
+      tokenProgram,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface ReclaimExpiredAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  nftToken?: PublicKey;
+  tokens: Array<AllocationTokenProgram>;
+}
+
+export function createReclaimExpiredAllocationInstruction(props: ReclaimExpiredAllocationInstructionProps): TransactionInstruction {
+  const allocation = allocationAddress(props.nftMint);
+  const remainingAccounts = props.tokens.flatMap(({ mint, tokenProgram }) => [
+    { pubkey: mint, isSigner: false, isWritable: false },
+    { pubkey: associatedTokenAddress(props.payer, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(allocation, mint, tokenProgram), isSigner: false, isWritable: true },
+    { pubkey: associatedTokenAddress(feeConfigAddress, mint, tokenProgram), isSigner: false, isWritable: true },
+  ]);
+  return fauxProgram.instruction.reclaimExpiredAllocation({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: props.nftToken ?? null,
+      allocation,
+      systemProgram: systemProgramId,
+      tokenProgram: tokenProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+    remainingAccounts,
+  });
+}
+
+export interface UpdateAllocationAccessInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  locked: boolean;
+  increaseAuthority?: PublicKey;
+}
+
+export function createUpdateAllocationAccessInstruction(props: UpdateAllocationAccessInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.updateAllocationAccess(props.locked, props.increaseAuthority ?? null, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      allocation: allocationAddress(props.nftMint),
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface RemoveSlotInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  tokenMint: PublicKey;
+}
+
+export function createRemoveSlotInstruction(props: RemoveSlotInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.removeSlot(props.tokenMint, {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      allocation: allocationAddress(props.nftMint),
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface FreezeAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  nftToken: PublicKey;
+}
+
+export function createFreezeAllocationInstruction(props: FreezeAllocationInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.freezeAllocation({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: props.nftToken,
+      allocation: allocationAddress(props.nftMint),
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export function createThawAllocationInstruction(props: FreezeAllocationInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.thawAllocation({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: props.nftToken,
+      allocation: allocationAddress(props.nftMint),
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface RecoverAllocationNftInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  oldNftToken: PublicKey;
+  newOwner: PublicKey;
+}
+
+export function createRecoverAllocationNftInstruction(props: RecoverAllocationNftInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.recoverAllocationNft({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      oldNftToken: props.oldNftToken,
+      newOwner: props.newOwner,
+      newNftToken: associatedTokenAddress(props.newOwner, props.nftMint, tokenExtensionsProgramId),
+      allocation: allocationAddress(props.nftMint),
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface EscrowAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  claimHash: Uint8Array;
+}
+
+export function createEscrowAllocationInstruction(props: EscrowAllocationInstructionProps): TransactionInstruction {
+  const claim = claimAddress(props.nftMint);
+  return fauxProgram.instruction.escrowAllocation(Array.from(props.claimHash), {
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      nftMint: props.nftMint,
+      nftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      allocation: allocationAddress(props.nftMint),
+      claim,
+      escrowNftToken: associatedTokenAddress(claim, props.nftMint, tokenExtensionsProgramId),
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface ClaimAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+  creator: PublicKey;
+}
+
+export function createClaimAllocationInstruction(props: ClaimAllocationInstructionProps): TransactionInstruction {
+  const claim = claimAddress(props.nftMint);
+  return fauxProgram.instruction.claimAllocation({
+    accounts: {
+      signer: props.payer,
+      nftMint: props.nftMint,
+      claim,
+      creator: props.creator,
+      escrowNftToken: associatedTokenAddress(claim, props.nftMint, tokenExtensionsProgramId),
+      signerNftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface CancelEscrowInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+}
+
+export function createCancelEscrowInstruction(props: CancelEscrowInstructionProps): TransactionInstruction {
+  const claim = claimAddress(props.nftMint);
+  return fauxProgram.instruction.cancelEscrow({
+    accounts: {
+      signer: props.payer,
+      nftMint: props.nftMint,
+      claim,
+      escrowNftToken: associatedTokenAddress(claim, props.nftMint, tokenExtensionsProgramId),
+      signerNftToken: associatedTokenAddress(props.payer, props.nftMint, tokenExtensionsProgramId),
+      systemProgram: systemProgramId,
+      tokenExtensionsProgram: tokenExtensionsProgramId,
+      associatedTokenProgram: associatedTokenProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface MigrateAllocationInstructionProps {
+  payer: PublicKey;
+  nftMint: PublicKey;
+}
+
+export function createMigrateAllocationInstruction(props: MigrateAllocationInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.migrateAllocation({
+    accounts: {
+      signer: props.payer,
+      nftMint: props.nftMint,
+      allocation: allocationAddress(props.nftMint),
+      systemProgram: systemProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
+
+export interface MigrateFeeConfigInstructionProps {
+  payer: PublicKey;
+}
+
+export function createMigrateFeeConfigInstruction(props: MigrateFeeConfigInstructionProps): TransactionInstruction {
+  return fauxProgram.instruction.migrateFeeConfig({
+    accounts: {
+      signer: props.payer,
+      feeConfig: feeConfigAddress,
+      systemProgram: systemProgramId,
+      eventAuthority: eventAuthorityAddress,
+      program: jewlProgramId,
+    },
+  });
+}
@@ -1,11 +1,17 @@
-import { AuthorityType, ExtensionType, LENGTH_SIZE, TYPE_SIZE, createInitializeMetadataPointerInstruction, createInitializeMintCloseAuthorityInstruction, createInitializeMintInstruction, createSetAuthorityInstruction, getMintLen } from "@solana/spl-token";
+import { AuthorityType, ExtensionType, LENGTH_SIZE, TYPE_SIZE, createInitializeMetadataPointerInstruction, createInitializeMintCloseAuthorityInstruction, createInitializeMintInstruction, createInitializePermanentDelegateInstruction, createSetAuthorityInstruction, getMintLen } from "@solana/spl-token";
 import type { Connection, Keypair, PublicKey, TransactionInstruction } from "@solana/web3.js";
 import { SystemProgram } from "@solana/web3.js";
 import { feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
 import type { TokenMetadata } from "@solana/spl-token-metadata";
 import { createInitializeInstruction, createUpdateAuthorityInstruction, createUpdateFieldInstruction, pack } from "@solana/spl-token-metadata";
+import type { AllocationMetadata } from "@/core/coder";
 
-export async function createNftMintInstructions(connection: Connection, mint: Keypair, payer: PublicKey, uri: string): Promise<Array<TransactionInstruction>> {
+export interface NftMintInstructions {
+  instructions: Array<TransactionInstruction>;
+  metadata: AllocationMetadata;
+}
+
+export async function createNftMintInstructions(connection: Connection, mint: Keypair, payer: PublicKey, uri: string): Promise<NftMintInstructions> {
   const response = await fetch(uri)
     .then(async x => await x.json() as unknown)
     .then(assertValidMetadata);
@@ -26,7 +32,7 @@ export async function createNftMintInstructions(connection: Connection, mint: Ke
 
   const metadataExtension = TYPE_SIZE + LENGTH_SIZE;
   const metadataSize = pack(metadata).length;
-  const mintSize = getMintLen([ExtensionType.MintCloseAuthority, ExtensionType.MetadataPointer]);
+  const mintSize = getMintLen([ExtensionType.MintCloseAuthority, ExtensionType.PermanentDelegate, ExtensionType.MetadataPointer]);
   const minRent = await connection.getMinimumBalanceForRentExemption(mintSize + metadataExtension + metadataSize);
 
   const createAccountInstruction = SystemProgram.createAccount({
@@ -43,6 +49,12 @@ export async function createNftMintInstructions(connection: Connection, mint: Ke
     tokenExtensionsProgramId,
   );
 
+  const initializePermanentDelegateInstruction = createInitializePermanentDelegateInstruction(
+    mint.publicKey,
+    feeConfigAddress,
+    tokenExtensionsProgramId,
+  );
+
   const initializeMetadataPointerInstruction = createInitializeMetadataPointerInstruction(
     mint.publicKey,
     null,
@@ -54,7 +66,7 @@ export async function createNftMintInstructions(connection: Connection, mint: Ke
     mint.publicKey,
     0,
     payer,
-    null,
+    feeConfigAddress,
     tokenExtensionsProgramId,
   );
 
@@ -77,10 +89,10 @@ export async function createNftMintInstructions(connection: Connection, mint: Ke
     value,
   }));
 
-  const renounceMetadataUpdateAuthority = createUpdateAuthorityInstruction({
+  const setMetadataUpdateAuthority = createUpdateAuthorityInstruction({
     metadata: mint.publicKey,
     oldAuthority: payer,
-    newAuthority: null,
+    newAuthority: feeConfigAddress,
     programId: tokenExtensionsProgramId,
   });
 
@@ -93,16 +105,22 @@ export async function createNftMintInstructions(connection: Connection, mint: Ke
     tokenExtensionsProgramId,
   );
 
-  return [
+  const instructions = [
     createAccountInstruction,
     initializeMintCloseAuthorityInstruction,
+    initializePermanentDelegateInstruction,
     initializeMetadataPointerInstruction,
     initializeMintInstruction,
     initializeMetadataInstruction,
     ...updateFieldInstructions,
-    renounceMetadataUpdateAuthority,
+    setMetadataUpdateAuthority,
     setMintAuthorityInstruction,
   ];
+
+  return {
+    instructions,
+    metadata: { name: metadata.name, symbol: metadata.symbol, uri: metadata.uri },
+  };
 }
 
 export interface NftMetadata {
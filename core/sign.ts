@@ -1,9 +1,10 @@
 import { PublicKey } from "@solana/web3.js";
 import type { Signer } from "@solana/web3.js";
 import base58 from "bs58";
-import { randomUUID } from "crypto";
+import { createHash, randomUUID } from "crypto";
 import { sign } from "tweetnacl";
 import util from "tweetnacl-util";
+import { claimAddress } from "@/core/address";
 import { unix } from "@/core/time";
 
 export interface Signature {
@@ -62,3 +63,13 @@ export async function getSignature(signer: Signer | Wallet, keyScope: string): P
 
   return `${base58.encode(decoded)}:${base58.encode(signature)}`;
 }
+
+export function claimHash(claimKey: PublicKey): Buffer {
+  // Escrows only store the hash of the claim key so the key stays secret until it is used to claim
+  return createHash("sha256").update(claimKey.toBuffer()).digest();
+}
+
+export function claimMessage(nftMint: PublicKey, claimant: PublicKey): Buffer {
+  // The claim key signs the escrow and the claimant so a signature cannot be replayed on another escrow
+  return Buffer.concat([claimAddress(nftMint).toBuffer(), claimant.toBuffer()]);
+}
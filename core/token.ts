@@ -17,10 +17,11 @@ export async function getTokenAccount(connection: Connection, accountAddress: Pu
   return { ...account, ...mint };
 }
 
-export async function getTokenAccounts(connection: Connection, accountAddresses: Array<PublicKey>, programId = tokenProgramId): Promise<Array<TokenAccount | null>> {
+export async function getTokenAccounts(connection: Connection, accountAddresses: Array<PublicKey>, programIds: PublicKey | Array<PublicKey> = tokenProgramId): Promise<Array<TokenAccount | null>> {
+  const programId = (i: number): PublicKey => Array.isArray(programIds) ? programIds[i] : programIds;
   const accountInfos = await getAccountsBatched(connection, accountAddresses);
   const tokenAccounts = accountInfos
-    .mapNonNull((x, i) => unpackAccount(accountAddresses[i], x, programId));
+    .mapNonNull((x, i) => unpackAccount(accountAddresses[i], x, programId(i)));
   const mintAddresses = tokenAccounts
     .mapNonNull(x => x.mint);
   const mintInfos = await getAccountsBatched(connection, mintAddresses);
@@ -29,7 +30,7 @@ export async function getTokenAccounts(connection: Connection, accountAddresses:
     const mintAddress = mintAddresses[i];
     const tokenAccount = tokenAccounts[i];
     if (mintInfo == null || mintAddress == null || tokenAccount == null) { return null; }
-    const mint = unpackMint(mintAddress, mintInfo, programId);
+    const mint = unpackMint(mintAddress, mintInfo, programId(i));
     return { ...tokenAccount, ...mint };
   });
 }
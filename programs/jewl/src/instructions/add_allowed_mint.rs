@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::allowlist::AllowedMintAccount;
+use crate::state::event::AllowedMintAdded;
+use crate::state::fee::FeeConfigAccount;
+
+pub fn add_allowed_mint(ctx: Context<AddAllowedMintState>) -> Result<()> {
+    ctx.accounts.allowed_mint.mint = ctx.accounts.token_mint.key();
+    ctx.accounts.allowed_mint.initialized = true;
+
+    emit_cpi!(AllowedMintAdded {
+        mint: ctx.accounts.token_mint.key(),
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddAllowedMintState<'info> {
+    #[account(
+        mut,
+        constraint = fee_config.fee_authority.key() == signer.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        constraint = token_mint.decimals > 0
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        seeds = [AllowedMintAccount::seed(), token_mint.key().as_ref()],
+        space = AllowedMintAccount::space(),
+        payer = signer,
+        bump,
+    )]
+    pub allowed_mint: Box<InterfaceAccount<'info, AllowedMintAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::state::claim::ClaimAccount;
+use crate::state::event::EscrowCancelled;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::signer::SeedSigner;
+
+pub fn cancel_escrow(ctx: Context<CancelEscrowState>) -> Result<()> {
+    let mint = ctx.accounts.nft_mint.key();
+    let bump = Pubkey::find_program_address(&[ClaimAccount::seed(), mint.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[ClaimAccount::seed(), mint.as_ref(), &[bump]]];
+
+    // Move the nft out of escrow back to the creator
+    CrossProgramInvocations::transfer_token(
+        ctx.accounts.escrow_nft_token.to_account_info(),
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.signer_nft_token.to_account_info(),
+        ctx.accounts.claim.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+        1,
+        0,
+    )?;
+
+    // Close the escrow token account and claim account and refund the creator
+    CrossProgramInvocations::close_token(
+        ctx.accounts.escrow_nft_token.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.claim.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+    )?;
+    ctx.accounts.claim
+        .close(ctx.accounts.signer.to_account_info())?;
+
+    emit_cpi!(EscrowCancelled {
+        nft_mint: mint,
+        creator: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelEscrowState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAccount::seed(), nft_mint.key().as_ref()],
+        constraint = claim.initialized,
+        constraint = claim.creator == signer.key(),
+        bump
+    )]
+    pub claim: Box<InterfaceAccount<'info, ClaimAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = claim,
+        associated_token::token_program = token_extensions_program,
+        constraint = escrow_nft_token.amount == 1
+    )]
+    pub escrow_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        payer = signer
+    )]
+    pub signer_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
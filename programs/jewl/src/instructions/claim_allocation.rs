@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::sysvar::instructions;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::state::claim::ClaimAccount;
+use crate::state::event::AllocationClaimed;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
+use crate::utility::signer::SeedSigner;
+use crate::utility::sigverify::ed25519_signer;
+
+pub fn claim_allocation(ctx: Context<ClaimAllocationState>) -> Result<()> {
+    // The claim key must have signed the claim address followed by the claimant so the signature only works for this escrow
+    let claimant = ctx.accounts.signer.key();
+    let message = [ctx.accounts.claim.key().as_ref(), claimant.as_ref()].concat();
+    let claim_key = ed25519_signer(&ctx.accounts.instructions, &message)?;
+    if hash(claim_key.as_ref()).to_bytes() != ctx.accounts.claim.claim_hash {
+        return Err(RuntimeError::InvalidClaimSignature.into());
+    }
+
+    let mint = ctx.accounts.nft_mint.key();
+    let bump = Pubkey::find_program_address(&[ClaimAccount::seed(), mint.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[ClaimAccount::seed(), mint.as_ref(), &[bump]]];
+
+    // Move the nft out of escrow to the claimant
+    CrossProgramInvocations::transfer_token(
+        ctx.accounts.escrow_nft_token.to_account_info(),
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.signer_nft_token.to_account_info(),
+        ctx.accounts.claim.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+        1,
+        0,
+    )?;
+
+    // Close the escrow token account and claim account and refund the creator
+    CrossProgramInvocations::close_token(
+        ctx.accounts.escrow_nft_token.to_account_info(),
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.claim.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+    )?;
+    ctx.accounts.claim
+        .close(ctx.accounts.creator.to_account_info())?;
+
+    emit_cpi!(AllocationClaimed {
+        nft_mint: mint,
+        claimant,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [ClaimAccount::seed(), nft_mint.key().as_ref()],
+        constraint = claim.initialized,
+        has_one = creator,
+        bump
+    )]
+    pub claim: Box<InterfaceAccount<'info, ClaimAccount>>,
+
+    /// CHECK: Receives the rent of the escrow, checked against the claim account
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = claim,
+        associated_token::token_program = token_extensions_program,
+        constraint = escrow_nft_token.amount == 1
+    )]
+    pub escrow_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        payer = signer
+    )]
+    pub signer_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: The instructions sysvar used to find the ed25519 instruction
+    #[account(address = instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
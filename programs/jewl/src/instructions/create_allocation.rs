@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as StateMint;
+use anchor_spl::token_2022::Token2022;
+
+use crate::id;
+use crate::instructions::initialize_allocation::initialize_allocation_account;
+use crate::state::allocation::{AllocationAccount, AllocationMetadata, VestingSchedule};
+use crate::state::event::AllocationInitialized;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::signer::SeedSigner;
+
+// Create a program derived nft mint with its metadata and mint it to the payer
+pub fn create_nft<'info>(
+    payer: AccountInfo<'info>,
+    fee_config: AccountInfo<'info>,
+    nft_mint: AccountInfo<'info>,
+    nft_token: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_extensions_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    mint_signer: SeedSigner,
+    metadata: AllocationMetadata,
+    soulbound: bool,
+) -> Result<()> {
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+
+    // Create the nft mint with the fee config as close, mint, freeze and metadata authority and permanent delegate
+    let mut extensions = vec![ExtensionType::MintCloseAuthority, ExtensionType::PermanentDelegate, ExtensionType::MetadataPointer];
+    if soulbound {
+        extensions.push(ExtensionType::NonTransferable);
+    }
+    let space = ExtensionType::try_calculate_account_len::<StateMint>(&extensions)?;
+    CrossProgramInvocations::create_account(
+        payer.clone(),
+        nft_mint.clone(),
+        system_program.clone(),
+        Some(mint_signer),
+        space,
+        token_extensions_program.key,
+    )?;
+    CrossProgramInvocations::initialize_mint_close_authority(
+        nft_mint.clone(),
+        token_extensions_program.clone(),
+        fee_config.key,
+    )?;
+    CrossProgramInvocations::initialize_permanent_delegate(
+        nft_mint.clone(),
+        token_extensions_program.clone(),
+        fee_config.key,
+    )?;
+
+    // A soulbound nft cannot be transferred by the holder
+    if soulbound {
+        CrossProgramInvocations::initialize_non_transferable(
+            nft_mint.clone(),
+            token_extensions_program.clone(),
+        )?;
+    }
+    CrossProgramInvocations::initialize_metadata_pointer(
+        nft_mint.clone(),
+        token_extensions_program.clone(),
+        nft_mint.key(),
+    )?;
+    CrossProgramInvocations::initialize_mint(
+        nft_mint.clone(),
+        token_extensions_program.clone(),
+        fee_config.key,
+        Some(fee_config.key),
+        0,
+    )?;
+    CrossProgramInvocations::initialize_metadata(
+        nft_mint.clone(),
+        fee_config.clone(),
+        token_extensions_program.clone(),
+        Some(signer),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+    CrossProgramInvocations::fund_account(
+        nft_mint.clone(),
+        payer.clone(),
+        system_program.clone(),
+    )?;
+
+    // Mint the nft to the payer
+    CrossProgramInvocations::create_token_account(
+        payer.clone(),
+        nft_token.clone(),
+        payer.clone(),
+        nft_mint.clone(),
+        system_program.clone(),
+        token_extensions_program.clone(),
+        associated_token_program.clone(),
+    )?;
+    CrossProgramInvocations::mint_token(
+        nft_mint.clone(),
+        nft_token.clone(),
+        fee_config.clone(),
+        token_extensions_program.clone(),
+        Some(signer),
+        1,
+    )?;
+
+    Ok(())
+}
+
+pub fn create_allocation(ctx: Context<CreateAllocationState>, nonce: u64, authority: Option<Pubkey>, vesting: Option<VestingSchedule>, expires_at: Option<i64>, metadata: AllocationMetadata, soulbound: bool) -> Result<()> {
+    let creator = ctx.accounts.signer.key();
+    let nonce_bytes = nonce.to_le_bytes();
+    let mint_signer: SeedSigner = &[&[AllocationAccount::mint_seed(), creator.as_ref(), nonce_bytes.as_ref(), &[ctx.bumps.nft_mint]]];
+
+    create_nft(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.nft_token.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        ctx.accounts.associated_token_program.to_account_info(),
+        mint_signer,
+        metadata,
+        soulbound,
+    )?;
+
+    initialize_allocation_account(
+        &mut ctx.accounts.allocation,
+        &ctx.accounts.fee_config.default_mints,
+        creator,
+        authority.unwrap_or_default(),
+        vesting,
+        expires_at,
+    )?;
+
+    emit_cpi!(AllocationInitialized {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        decrease_authority: ctx.accounts.allocation.decrease_authority,
+        recover_authority: ctx.accounts.allocation.recover_authority,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CreateAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    /// CHECK: Created and initialized in the instruction
+    #[account(
+        mut,
+        seeds = [AllocationAccount::mint_seed(), signer.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the instruction by the associated token program
+    #[account(mut)]
+    pub nft_token: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        space = AllocationAccount::space(fee_config.default_mints.len()),
+        payer = signer,
+        bump,
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
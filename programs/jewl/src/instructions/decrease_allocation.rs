@@ -1,84 +1,98 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::Token;
-use anchor_spl::token_interface::{TokenAccount, Mint};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface};
 
 use crate::id;
+use crate::instructions::exercise_allocation::withdraw_slot;
 use crate::state::allocation::AllocationAccount;
+use crate::state::event::AllocationDecreased;
 use crate::state::fee::FeeConfigAccount;
 use crate::utility::cpi::CrossProgramInvocations;
 use crate::utility::error::RuntimeError;
 use crate::utility::signer::SeedSigner;
 
-pub fn decrease_allocation(ctx: Context<DecreaseAllocationState>, amount: u64) -> Result<()> {
-    let expected_amount: u64;
-
-    if ctx.accounts.allocation.first_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.first_token_amount;
-        ctx.accounts.allocation.first_token_amount = ctx.accounts.allocation.first_token_amount
-            .checked_sub(amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else if ctx.accounts.allocation.second_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.second_token_amount;
-        ctx.accounts.allocation.second_token_amount = ctx.accounts.allocation.second_token_amount
-            .checked_sub(amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else if ctx.accounts.allocation.third_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.third_token_amount;
-        ctx.accounts.allocation.third_token_amount = ctx.accounts.allocation.third_token_amount
-            .checked_sub(amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else {
-        return Err(RuntimeError::TokenNotFound.into());
+// The wallet that receives the tokens, the recipient if provided and otherwise the signer
+fn payout_wallet<'info>(recipient: &Option<UncheckedAccount<'info>>, signer: &Signer<'info>) -> AccountInfo<'info> {
+    match recipient {
+        Some(recipient) => recipient.to_account_info(),
+        None => signer.to_account_info(),
     }
+}
 
-    let mint = ctx.accounts.nft_mint.key();
-    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), mint.as_ref()], &id()).1;
-    let signer: SeedSigner = &[&[AllocationAccount::seed(), mint.as_ref(), &[bump]]];
-
-    // If there is more tokens than expected transfer the difference to fee account
-    if ctx.accounts.allocation_token_account.amount > expected_amount {
-        let transfer_amount = ctx.accounts.allocation_token_account.amount
-            .checked_sub(expected_amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-        CrossProgramInvocations::transfer_token(
-            ctx.accounts.allocation_token_account.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.fee_token_account.to_account_info(),
-            ctx.accounts.allocation.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            Some(signer),
-            transfer_amount,
-            ctx.accounts.token_mint.decimals,
-        )?;
+pub fn decrease_allocation(ctx: Context<DecreaseAllocationState>, amount: u64, unwrap: bool) -> Result<()> {
+    if unwrap && ctx.accounts.token_mint.key() != native_mint::ID {
+        return Err(RuntimeError::NotNativeMint.into());
+    }
+
+    // The temporary account is closed within the instruction so it can not be left behind when not unwrapping
+    if !unwrap && ctx.accounts.unwrap_token_account.is_some() {
+        return Err(ProgramError::InvalidInstructionData.into());
     }
 
-    // Transfer the amount from the allocation account to the signer account
-    CrossProgramInvocations::transfer_token(
-        ctx.accounts.allocation_token_account.to_account_info(),
-        ctx.accounts.token_mint.to_account_info(),
-        ctx.accounts.signer_token_account.to_account_info(),
-        ctx.accounts.allocation.to_account_info(),
+    // Unwrapping pays out through a temporary wrapped sol account instead of the recipient token account
+    let payout_token_account = if unwrap {
+        &ctx.accounts.unwrap_token_account
+    } else {
+        &ctx.accounts.signer_token_account
+    };
+    let payout_token_account = payout_token_account.as_ref()
+        .ok_or(ErrorCode::AccountNotEnoughKeys)?;
+
+    let token_mint = ctx.accounts.token_mint.key();
+    let index = ctx.accounts.allocation.tokens
+        .iter()
+        .position(|x| x.mint == token_mint)
+        .ok_or(RuntimeError::TokenNotFound)?;
+    let token = &mut ctx.accounts.allocation.tokens[index];
+    token.amount = token.amount
+        .checked_sub(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let programs = [
+        ctx.accounts.system_program.to_account_info(),
         ctx.accounts.token_program.to_account_info(),
-        Some(signer),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    let excess_amount = withdraw_slot(
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.fee_config,
+        &ctx.accounts.nft_mint,
+        &ctx.accounts.allocation,
+        &ctx.accounts.token_mint,
+        payout_token_account,
+        &ctx.accounts.allocation_token_account,
+        &ctx.accounts.fee_token_account,
+        &programs,
         amount,
-        ctx.accounts.token_mint.decimals,
     )?;
 
-    // If the allocation token account is now empty, close it
-    if expected_amount == amount {
+    // Unwrap by closing the temporary account straight to the recipient wallet, releasing the amount and its rent as lamports
+    if unwrap {
+        let mint = ctx.accounts.nft_mint.key();
+        let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), mint.as_ref()], &id()).1;
+        let signer: SeedSigner = &[&[AllocationAccount::seed(), mint.as_ref(), &[bump]]];
         CrossProgramInvocations::close_token(
-            ctx.accounts.allocation_token_account.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
+            payout_token_account.to_account_info(),
+            payout_wallet(&ctx.accounts.recipient, &ctx.accounts.signer),
             ctx.accounts.allocation.to_account_info(),
             ctx.accounts.token_program.to_account_info(),
             Some(signer),
         )?;
     }
 
+    emit_cpi!(AllocationDecreased {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        mint: token_mint,
+        amount,
+        excess: excess_amount,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct DecreaseAllocationState<'info> {
     #[account(
@@ -90,20 +104,22 @@ pub struct DecreaseAllocationState<'info> {
     #[account(
         seeds = [FeeConfigAccount::seed()],
         bump,
-        constraint = fee_config.initialized
+        constraint = fee_config.is_current()
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
     #[account(
+        mut,
         mint::authority = fee_config,
         mint::decimals = 0,
+        mint::token_program = token_extensions_program,
     )]
     pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
-        constraint = allocation.initialized,
+        constraint = allocation.is_current(),
         bump
     )]
     pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
@@ -114,14 +130,29 @@ pub struct DecreaseAllocationState<'info> {
     )]
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// CHECK: Any wallet or program account can receive the tokens
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
     #[account(
         init_if_needed,
         associated_token::mint = token_mint,
-        associated_token::authority = signer,
+        associated_token::authority = payout_wallet(&recipient, &signer),
         associated_token::token_program = token_program,
         payer = signer
     )]
-    pub signer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub signer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init,
+        seeds = [AllocationAccount::unwrap_seed(), nft_mint.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = allocation,
+        token::token_program = token_program,
+        payer = signer
+    )]
+    pub unwrap_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
@@ -144,7 +175,10 @@ pub struct DecreaseAllocationState<'info> {
     pub system_program: Program<'info, System>,
 
     #[account()]
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
 
     #[account()]
     pub associated_token_program: Program<'info, AssociatedToken>,
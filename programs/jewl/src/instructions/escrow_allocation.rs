@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::state::allocation::AllocationAccount;
+use crate::state::claim::ClaimAccount;
+use crate::state::event::AllocationEscrowed;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+
+pub fn escrow_allocation(ctx: Context<EscrowAllocationState>, claim_hash: [u8; 32]) -> Result<()> {
+    // Move the nft into escrow until it is claimed with a signature of the claim key
+    CrossProgramInvocations::transfer_token(
+        ctx.accounts.nft_token.to_account_info(),
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.escrow_nft_token.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        None,
+        1,
+        0,
+    )?;
+
+    // Close the nft token account and reclaim rent
+    CrossProgramInvocations::close_token(
+        ctx.accounts.nft_token.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        None,
+    )?;
+
+    // Only the hash of the claim key is passed and stored so the claim key stays secret until claiming
+    ctx.accounts.claim.creator = ctx.accounts.signer.key();
+    ctx.accounts.claim.claim_hash = claim_hash;
+    ctx.accounts.claim.initialized = true;
+
+    emit_cpi!(AllocationEscrowed {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        creator: ctx.accounts.claim.creator,
+        claim_hash: ctx.accounts.claim.claim_hash,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct EscrowAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        constraint = nft_token.amount == 1,
+        constraint = !nft_token.is_frozen()
+    )]
+    pub nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account(
+        init,
+        seeds = [ClaimAccount::seed(), nft_mint.key().as_ref()],
+        space = ClaimAccount::space(),
+        payer = signer,
+        bump,
+    )]
+    pub claim: Box<InterfaceAccount<'info, ClaimAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = nft_mint,
+        associated_token::authority = claim,
+        associated_token::token_program = token_extensions_program,
+        payer = signer
+    )]
+    pub escrow_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
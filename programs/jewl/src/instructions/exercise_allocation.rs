@@ -1,45 +1,61 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::spl_token::native_mint;
 use anchor_spl::token::Token;
 use anchor_spl::token_2022::Token2022;
 use anchor_spl::token_interface::{TokenAccount, Mint};
 
 use crate::id;
 use crate::state::allocation::AllocationAccount;
+use crate::state::event::{AllocationExercised, ExercisedToken};
 use crate::state::fee::FeeConfigAccount;
 use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
+use crate::utility::metadata::sync_slot_metadata;
 use crate::utility::signer::SeedSigner;
+use crate::utility::token::{find_program, has_withheld_fees};
 
-fn withdraw_token<'info>(
-    nft_mint: &InterfaceAccount<'info, Mint>,
-    signer_token: &InterfaceAccount<'info, TokenAccount>,
+// If there are more tokens than can be withdrawn, transfer the difference to the fee account
+fn sweep_excess<'info>(
     allocation_token: &InterfaceAccount<'info, TokenAccount>,
     fee_token: &InterfaceAccount<'info, TokenAccount>,
     allocation: &InterfaceAccount<'info, AllocationAccount>,
     token_mint: &InterfaceAccount<'info, Mint>,
-    token_program: &Program<'info, Token>,
+    token_program: &AccountInfo<'info>,
+    signer: SeedSigner,
     amount: u64
-) -> Result<()> {
-    let nft_mint_key = nft_mint.key();
-    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), nft_mint_key.as_ref()], &id()).1;
-    let signer: SeedSigner = &[&[AllocationAccount::seed(), nft_mint_key.as_ref(), &[bump]]];
-
-    // If there are more tokens than the signer can withdraw, transfer the difference to the fee account
-    if allocation_token.amount > amount {
-        let transfer_amount = allocation_token.amount
-            .checked_sub(amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
+) -> Result<u64> {
+    let excess_amount = allocation_token.amount.saturating_sub(amount);
+    if excess_amount > 0 {
         CrossProgramInvocations::transfer_token(
             allocation_token.to_account_info(),
             token_mint.to_account_info(),
             fee_token.to_account_info(),
             allocation.to_account_info(),
-            token_program.to_account_info(),
+            token_program.clone(),
             Some(signer),
-            transfer_amount,
+            excess_amount,
             token_mint.decimals,
         )?;
     }
+    Ok(excess_amount)
+}
+
+pub fn withdraw_token<'info>(
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    signer_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation_token: &InterfaceAccount<'info, TokenAccount>,
+    fee_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation: &InterfaceAccount<'info, AllocationAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+    amount: u64
+) -> Result<u64> {
+    let nft_mint_key = nft_mint.key();
+    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), nft_mint_key.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[AllocationAccount::seed(), nft_mint_key.as_ref(), &[bump]]];
+
+    let excess_amount = sweep_excess(allocation_token, fee_token, allocation, token_mint, token_program, signer, amount)?;
 
     // Transfer the amount from the allocation account to the signer account
     if amount > 0 {
@@ -48,100 +64,283 @@ fn withdraw_token<'info>(
             token_mint.to_account_info(),
             signer_token.to_account_info(),
             allocation.to_account_info(),
-            token_program.to_account_info(),
+            token_program.clone(),
             Some(signer),
             amount,
             token_mint.decimals,
         )?;
     }
 
-    // Close allocation token account and reclaim rent
+    // Close allocation token account and reclaim rent unless it still holds withheld transfer fees
+    if !has_withheld_fees(&allocation_token.to_account_info())? {
+        CrossProgramInvocations::close_token(
+            allocation_token.to_account_info(),
+            signer_token.to_account_info(),
+            allocation.to_account_info(),
+            token_program.clone(),
+            Some(signer),
+        )?;
+    }
+
+    Ok(excess_amount)
+}
+
+// Unwrap by closing the wrapped sol allocation token account straight to the recipient wallet
+pub fn unwrap_token<'info>(
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    recipient: &AccountInfo<'info>,
+    allocation_token: &InterfaceAccount<'info, TokenAccount>,
+    fee_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation: &InterfaceAccount<'info, AllocationAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &AccountInfo<'info>,
+    amount: u64
+) -> Result<u64> {
+    let nft_mint_key = nft_mint.key();
+    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), nft_mint_key.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[AllocationAccount::seed(), nft_mint_key.as_ref(), &[bump]]];
+
+    let excess_amount = sweep_excess(allocation_token, fee_token, allocation, token_mint, token_program, signer, amount)?;
+
+    // Closing a wrapped sol account releases both the wrapped amount and the rent as lamports
     CrossProgramInvocations::close_token(
         allocation_token.to_account_info(),
-        signer_token.to_account_info(),
+        recipient.clone(),
         allocation.to_account_info(),
-        token_program.to_account_info(),
+        token_program.clone(),
         Some(signer),
     )?;
 
-    Ok(())
+    Ok(excess_amount)
 }
 
-pub fn exercise_allocation(ctx: Context<ExerciseAllocationState>) -> Result<()> {
-    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
-    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+// Pay out part of a slot whose amount was already reduced and keep the nft metadata in sync with it
+pub fn withdraw_slot<'info>(
+    payer: &AccountInfo<'info>,
+    fee_config: &InterfaceAccount<'info, FeeConfigAccount>,
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    allocation: &InterfaceAccount<'info, AllocationAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    payout_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation_token: &InterfaceAccount<'info, TokenAccount>,
+    fee_token: &InterfaceAccount<'info, TokenAccount>,
+    programs: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<u64> {
+    let system_program = find_program(programs, &System::id())?;
+    let token_extensions_program = find_program(programs, &Token2022::id())?;
+    let token_program = find_program(programs, token_mint.to_account_info().owner)?;
+
+    let index = allocation.tokens
+        .iter()
+        .position(|x| x.mint == token_mint.key())
+        .ok_or(RuntimeError::TokenNotFound)?;
+    let token = allocation.tokens[index];
+    let expected_amount = token.amount
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    // Withdraw the first token to signer
-    withdraw_token(
-        &ctx.accounts.nft_mint,
-        &ctx.accounts.signer_first_token_account,
-        &ctx.accounts.allocation_first_token_account,
-        &ctx.accounts.fee_first_token_account,
-        &ctx.accounts.allocation,
-        &ctx.accounts.first_token_mint,
-        &ctx.accounts.token_program,
-        ctx.accounts.allocation.first_token_amount,
-    )?;
+    let nft_mint_key = nft_mint.key();
+    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), nft_mint_key.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[AllocationAccount::seed(), nft_mint_key.as_ref(), &[bump]]];
 
-    // Withdraw the second token to signer
-    withdraw_token(
-        &ctx.accounts.nft_mint,
-        &ctx.accounts.signer_second_token_account,
-        &ctx.accounts.allocation_second_token_account,
-        &ctx.accounts.fee_second_token_account,
-        &ctx.accounts.allocation,
-        &ctx.accounts.second_token_mint,
-        &ctx.accounts.token_program,
-        ctx.accounts.allocation.second_token_amount,
-    )?;
+    let excess_amount = sweep_excess(allocation_token, fee_token, allocation, token_mint, token_program, signer, expected_amount)?;
 
-    // Withdraw the third token to signer
-    withdraw_token(
-        &ctx.accounts.nft_mint,
-        &ctx.accounts.signer_third_token_account,
-        &ctx.accounts.allocation_third_token_account,
-        &ctx.accounts.fee_third_token_account,
-        &ctx.accounts.allocation,
-        &ctx.accounts.third_token_mint,
-        &ctx.accounts.token_program,
-        ctx.accounts.allocation.third_token_amount,
+    // Transfer the amount from the allocation account to the payout account
+    if amount > 0 {
+        CrossProgramInvocations::transfer_token(
+            allocation_token.to_account_info(),
+            token_mint.to_account_info(),
+            payout_token.to_account_info(),
+            allocation.to_account_info(),
+            token_program.clone(),
+            Some(signer),
+            amount,
+            token_mint.decimals,
+        )?;
+    }
+
+    // If the allocation token account is now empty, close it unless it still holds withheld transfer fees
+    if token.amount == 0 && !has_withheld_fees(&allocation_token.to_account_info())? {
+        CrossProgramInvocations::close_token(
+            allocation_token.to_account_info(),
+            payer.clone(),
+            allocation.to_account_info(),
+            token_program.clone(),
+            Some(signer),
+        )?;
+    }
+
+    sync_slot_metadata(
+        nft_mint.to_account_info(),
+        fee_config.to_account_info(),
+        payer.clone(),
+        system_program.clone(),
+        token_extensions_program.clone(),
+        &token,
+        index,
     )?;
 
+    Ok(excess_amount)
+}
+
+// Withdraw every token of the allocation to the recipient and close the nft and allocation
+pub fn exercise_nft<'info>(
+    signer: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    fee_config: &InterfaceAccount<'info, FeeConfigAccount>,
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    nft_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation: &InterfaceAccount<'info, AllocationAccount>,
+    token_accounts: &'info [AccountInfo<'info>],
+    programs: &[AccountInfo<'info>],
+    unwrap: bool,
+) -> Result<AllocationExercised> {
+    let system_program = find_program(programs, &System::id())?;
+    let token_extensions_program = find_program(programs, &Token2022::id())?;
+    let associated_token_program = find_program(programs, &AssociatedToken::id())?;
+
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let fee_signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+
+    // A vesting allocation can only be exercised once fully vested
+    if let Some(vesting) = allocation.vesting {
+        if Clock::get()?.unix_timestamp < vesting.end_timestamp {
+            return Err(RuntimeError::AllocationNotVested.into());
+        }
+    }
+
+    // Every token requires its mint, recipient, allocation and fee token account
+    let tokens = allocation.tokens.clone();
+    if token_accounts.len() != tokens.len() * 4 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+
+    // Withdraw each token to recipient
+    let mut exercised = Vec::with_capacity(tokens.len());
+    for (token, accounts) in tokens.iter().zip(token_accounts.chunks(4)) {
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.key() != token.mint || *token_mint.to_account_info().owner != token.token_program {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(programs, &token.token_program)?;
+
+        // Unwrapped sol goes straight to the recipient wallet so its token account is not needed
+        let unwrap_token_account = unwrap && token.mint == native_mint::ID;
+
+        // Create the token accounts if needed, this also verifies the addresses
+        for (account, authority) in accounts[1..].iter().zip([
+            recipient.clone(),
+            allocation.to_account_info(),
+            fee_config.to_account_info(),
+        ]).skip(usize::from(unwrap_token_account)) {
+            CrossProgramInvocations::create_token_account(
+                signer.clone(),
+                account.to_account_info(),
+                authority,
+                token_mint.to_account_info(),
+                system_program.clone(),
+                token_program.clone(),
+                associated_token_program.clone(),
+            )?;
+        }
+
+        let excess_amount = if unwrap_token_account {
+            unwrap_token(
+                nft_mint,
+                recipient,
+                &InterfaceAccount::try_from(&accounts[2])?,
+                &InterfaceAccount::try_from(&accounts[3])?,
+                allocation,
+                &token_mint,
+                token_program,
+                token.amount,
+            )?
+        } else {
+            withdraw_token(
+                nft_mint,
+                &InterfaceAccount::try_from(&accounts[1])?,
+                &InterfaceAccount::try_from(&accounts[2])?,
+                &InterfaceAccount::try_from(&accounts[3])?,
+                allocation,
+                &token_mint,
+                token_program,
+                token.amount,
+            )?
+        };
+        exercised.push(ExercisedToken { mint: token.mint, amount: token.amount, excess: excess_amount });
+    }
+
     // Burn allocation nft
     CrossProgramInvocations::burn_token(
-        ctx.accounts.nft_mint.to_account_info(),
-        ctx.accounts.nft_token.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.token_extensions_program.to_account_info(),
+        nft_mint.to_account_info(),
+        nft_token.to_account_info(),
+        signer.clone(),
+        token_extensions_program.clone(),
         None,
         1,
     )?;
 
     // Close the nft token account and reclaim rent
     CrossProgramInvocations::close_token(
-        ctx.accounts.nft_token.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.token_extensions_program.to_account_info(),
+        nft_token.to_account_info(),
+        signer.clone(),
+        signer.clone(),
+        token_extensions_program.clone(),
         None,
     )?;
 
     // Close the nft token mint and reclaim rent
     CrossProgramInvocations::close_token(
-        ctx.accounts.nft_mint.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.fee_config.to_account_info(),
-        ctx.accounts.token_extensions_program.to_account_info(),
-        Some(signer),
+        nft_mint.to_account_info(),
+        signer.clone(),
+        fee_config.to_account_info(),
+        token_extensions_program.clone(),
+        Some(fee_signer),
     )?;
 
     // Close the allocation account and reclaim rent
-    ctx.accounts.allocation
-        .close(ctx.accounts.signer.to_account_info())?;
+    allocation.close(signer.clone())?;
+
+    Ok(AllocationExercised {
+        nft_mint: nft_mint.key(),
+        owner: signer.key(),
+        tokens: exercised,
+    })
+}
+
+pub fn exercise_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, ExerciseAllocationState<'info>>, unwrap: bool) -> Result<()> {
+    // Pay out to the recipient if provided, otherwise to the signer
+    let recipient = match &ctx.accounts.recipient {
+        Some(recipient) => recipient.to_account_info(),
+        None => ctx.accounts.signer.to_account_info(),
+    };
+
+    let event = exercise_nft(
+        &ctx.accounts.signer.to_account_info(),
+        &recipient,
+        &ctx.accounts.fee_config,
+        &ctx.accounts.nft_mint,
+        &ctx.accounts.nft_token,
+        &ctx.accounts.allocation,
+        ctx.remaining_accounts,
+        &[
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            ctx.accounts.associated_token_program.to_account_info(),
+        ],
+        unwrap,
+    )?;
+
+    emit_cpi!(event);
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ExerciseAllocationState<'info> {
     #[account(mut)]
@@ -150,7 +349,7 @@ pub struct ExerciseAllocationState<'info> {
     #[account(
         seeds = [FeeConfigAccount::seed()],
         bump,
-        constraint = fee_config.initialized
+        constraint = fee_config.is_current()
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
@@ -174,109 +373,14 @@ pub struct ExerciseAllocationState<'info> {
     #[account(
         mut,
         seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
-        constraint = allocation.initialized,
+        constraint = allocation.is_current(),
         bump
     )]
     pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
 
-    #[account(
-        mint::token_program = token_program,
-        constraint = first_token_mint.key() == allocation.first_token_mint.key()
-    )]
-    pub first_token_mint: Box<InterfaceAccount<'info, Mint>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = first_token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub signer_first_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = first_token_mint,
-        associated_token::authority = allocation,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub allocation_first_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = first_token_mint,
-        associated_token::authority = fee_config,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub fee_first_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        mint::token_program = token_program,
-        constraint = second_token_mint.key() == allocation.second_token_mint.key()
-    )]
-    pub second_token_mint: Box<InterfaceAccount<'info, Mint>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = second_token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub signer_second_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = second_token_mint,
-        associated_token::authority = allocation,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub allocation_second_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = second_token_mint,
-        associated_token::authority = fee_config,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub fee_second_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        mint::token_program = token_program,
-        constraint = third_token_mint.key() == allocation.third_token_mint.key()
-    )]
-    pub third_token_mint: Box<InterfaceAccount<'info, Mint>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = third_token_mint,
-        associated_token::authority = signer,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub signer_third_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = third_token_mint,
-        associated_token::authority = allocation,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub allocation_third_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
-
-    #[account(
-        init_if_needed,
-        associated_token::mint = third_token_mint,
-        associated_token::authority = fee_config,
-        associated_token::token_program = token_program,
-        payer = signer
-    )]
-    pub fee_third_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: Any wallet or program account can receive the tokens
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
 
     #[account()]
     pub system_program: Program<'info, System>,
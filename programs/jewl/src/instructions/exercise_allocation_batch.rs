@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{AssociatedToken, get_associated_token_address_with_program_id};
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::instructions::exercise_allocation::exercise_nft;
+use crate::state::allocation::AllocationAccount;
+use crate::state::fee::FeeConfigAccount;
+
+pub fn exercise_allocation_batch<'info>(ctx: Context<'_, '_, 'info, 'info, ExerciseAllocationBatchState<'info>>, unwrap: bool) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+
+    // Pay out to the recipient if provided, otherwise to the signer
+    let recipient = match &ctx.accounts.recipient {
+        Some(recipient) => recipient.to_account_info(),
+        None => ctx.accounts.signer.to_account_info(),
+    };
+    let programs = [
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        ctx.accounts.associated_token_program.to_account_info(),
+    ];
+
+    // Every allocation requires its nft mint, nft token account and allocation followed by the accounts of each token
+    let mut accounts = ctx.remaining_accounts;
+    if accounts.is_empty() {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+    while !accounts.is_empty() {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+
+        let nft_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if *accounts[0].owner != Token2022::id()
+            || nft_mint.mint_authority != Some(ctx.accounts.fee_config.key()).into()
+            || nft_mint.decimals != 0 {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        let nft_token = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        if nft_token.key() != get_associated_token_address_with_program_id(&signer, &nft_mint.key(), &Token2022::id()) || nft_token.amount != 1 {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        let allocation = InterfaceAccount::<AllocationAccount>::try_from(&accounts[2])?;
+        let allocation_address = Pubkey::find_program_address(&[AllocationAccount::seed(), nft_mint.key().as_ref()], &id()).0;
+        if allocation.key() != allocation_address || !allocation.is_current() {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        let end = allocation.tokens.len()
+            .checked_mul(4)
+            .and_then(|x| x.checked_add(3))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if accounts.len() < end {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+
+        let event = exercise_nft(
+            &ctx.accounts.signer.to_account_info(),
+            &recipient,
+            &ctx.accounts.fee_config,
+            &nft_mint,
+            &nft_token,
+            &allocation,
+            &accounts[3..end],
+            &programs,
+            unwrap,
+        )?;
+        emit_cpi!(event);
+
+        accounts = &accounts[end..];
+    }
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExerciseAllocationBatchState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    /// CHECK: Any wallet or program account can receive the tokens
+    #[account(mut)]
+    pub recipient: Option<UncheckedAccount<'info>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::AllocationFrozen;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::signer::SeedSigner;
+
+pub fn freeze_allocation(ctx: Context<FreezeAllocationState>) -> Result<()> {
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+
+    // Freeze the holder's nft account while a dispute is investigated
+    CrossProgramInvocations::freeze_token(
+        ctx.accounts.nft_token.to_account_info(),
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+    )?;
+
+    emit_cpi!(AllocationFrozen {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        owner: ctx.accounts.nft_token.owner,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeAllocationState<'info> {
+    #[account(
+        constraint = signer.key() == allocation.recover_authority.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mint::authority = fee_config,
+        mint::freeze_authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_extensions_program,
+        constraint = nft_token.amount == 1
+    )]
+    pub nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+}
@@ -1,21 +1,37 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::Token;
-use anchor_spl::token_interface::{TokenAccount, Mint};
+use anchor_spl::token::spl_token::native_mint;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface};
 
 use crate::id;
-use crate::state::allocation::AllocationAccount;
+use crate::state::allocation::{AllocationAccount, AllocationToken};
+use crate::state::allowlist::AllowedMintAccount;
+use crate::state::event::AllocationIncreased;
 use crate::state::fee::FeeConfigAccount;
 use crate::utility::cpi::CrossProgramInvocations;
 use crate::utility::error::RuntimeError;
+use crate::utility::metadata::sync_slot_metadata;
 use crate::utility::signer::SeedSigner;
+use crate::utility::token::has_unsafe_extensions;
 
 const MAX_BPS: u16 = 10000;
 
-pub fn increase_allocation(ctx: Context<IncreaseAllocationState>, amount: u64) -> Result<()> {
-    let expected_amount: u64;
+// Deposit into the slot of the token, splitting off the protocol fee and sweeping any excess to the fee account
+pub fn deposit_token<'info>(
+    nft_mint: &InterfaceAccount<'info, Mint>,
+    allocation: &mut InterfaceAccount<'info, AllocationAccount>,
+    fee_config: &FeeConfigAccount,
+    signer: &AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    signer_token: &InterfaceAccount<'info, TokenAccount>,
+    allocation_token: &mut InterfaceAccount<'info, TokenAccount>,
+    fee_token: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<(usize, AllocationIncreased)> {
     let allocation_amount = amount
-        .checked_mul(MAX_BPS.saturating_sub(ctx.accounts.fee_config.fee_bps).into())
+        .checked_mul(MAX_BPS.saturating_sub(fee_config.fee_bps).into())
         .ok_or(ProgramError::ArithmeticOverflow)?
         .checked_div(MAX_BPS.into())
         .ok_or(ProgramError::ArithmeticOverflow)?;
@@ -23,84 +39,182 @@ pub fn increase_allocation(ctx: Context<IncreaseAllocationState>, amount: u64) -
         .checked_sub(allocation_amount)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    if ctx.accounts.allocation.first_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.first_token_amount;
-        ctx.accounts.allocation.first_token_amount = ctx.accounts.allocation.first_token_amount
-            .checked_add(allocation_amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else if ctx.accounts.allocation.second_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.second_token_amount;
-        ctx.accounts.allocation.second_token_amount = ctx.accounts.allocation.second_token_amount
-            .checked_add(allocation_amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else if ctx.accounts.allocation.third_token_mint == ctx.accounts.token_mint.key() {
-        expected_amount = ctx.accounts.allocation.third_token_amount;
-        ctx.accounts.allocation.third_token_amount = ctx.accounts.allocation.third_token_amount
-            .checked_add(allocation_amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-    } else if ctx.accounts.allocation.first_token_amount == 0 {
-        expected_amount = 0;
-        ctx.accounts.allocation.first_token_mint = ctx.accounts.token_mint.key();
-        ctx.accounts.allocation.first_token_amount = allocation_amount;
-    } else if ctx.accounts.allocation.second_token_amount == 0 {
-        expected_amount = 0;
-        ctx.accounts.allocation.second_token_mint = ctx.accounts.token_mint.key();
-        ctx.accounts.allocation.second_token_amount = allocation_amount;
-    } else if ctx.accounts.allocation.third_token_amount == 0 {
-        expected_amount = 0;
-        ctx.accounts.allocation.third_token_mint = ctx.accounts.token_mint.key();
-        ctx.accounts.allocation.third_token_amount = allocation_amount;
+    // Find the slot for the token, either the existing one, an empty one or a new one
+    let token_mint_key = token_mint.key();
+    let max_tokens = usize::from(fee_config.max_tokens);
+    let tokens = &allocation.tokens;
+    let (index, expected_amount) = if let Some(index) = tokens.iter().position(|x| x.mint == token_mint_key) {
+        (index, tokens[index].amount)
+    } else if allocation.locked {
+        return Err(RuntimeError::AllocationLocked.into());
+    } else if let Some(index) = tokens.iter().position(|x| x.amount == 0) {
+        (index, 0)
+    } else if tokens.len() < max_tokens {
+        (tokens.len(), 0)
     } else {
         return Err(RuntimeError::MaxTokensReached.into());
+    };
+
+    // Only check the extensions when the mint is first added so existing slots can still be topped up
+    if (index == tokens.len() || tokens[index].mint != token_mint_key) && has_unsafe_extensions(&token_mint.to_account_info())? {
+        return Err(RuntimeError::UnsafeMintExtension.into());
     }
 
     // If there is more tokens than expected transfer the difference to fee account
-    if ctx.accounts.allocation_token_account.amount > expected_amount {
-        let mint = ctx.accounts.nft_mint.key();
+    let excess_amount = allocation_token.amount.saturating_sub(expected_amount);
+    if excess_amount > 0 {
+        let mint = nft_mint.key();
         let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), mint.as_ref()], &id()).1;
         let signer: SeedSigner = &[&[AllocationAccount::seed(), mint.as_ref(), &[bump]]];
-        let transfer_amount = ctx.accounts.allocation_token_account.amount
-            .checked_sub(expected_amount)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
         CrossProgramInvocations::transfer_token(
-            ctx.accounts.allocation_token_account.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.fee_token_account.to_account_info(),
-            ctx.accounts.allocation.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
+            allocation_token.to_account_info(),
+            token_mint.to_account_info(),
+            fee_token.to_account_info(),
+            allocation.to_account_info(),
+            token_program.clone(),
             Some(signer),
-            transfer_amount,
-            ctx.accounts.token_mint.decimals,
+            excess_amount,
+            token_mint.decimals,
         )?;
     }
 
     // Transfer the allocation amount to the allocation
     CrossProgramInvocations::transfer_token(
-        ctx.accounts.signer_token_account.to_account_info(),
-        ctx.accounts.token_mint.to_account_info(),
-        ctx.accounts.allocation_token_account.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        signer_token.to_account_info(),
+        token_mint.to_account_info(),
+        allocation_token.to_account_info(),
+        signer.clone(),
+        token_program.clone(),
         None,
         allocation_amount,
-        ctx.accounts.token_mint.decimals,
+        token_mint.decimals,
     )?;
 
     // Transfer the protocol fee to the fee account
     CrossProgramInvocations::transfer_token(
-        ctx.accounts.signer_token_account.to_account_info(),
-        ctx.accounts.token_mint.to_account_info(),
-        ctx.accounts.fee_token_account.to_account_info(),
-        ctx.accounts.signer.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
+        signer_token.to_account_info(),
+        token_mint.to_account_info(),
+        fee_token.to_account_info(),
+        signer.clone(),
+        token_program.clone(),
         None,
         fee_amount,
-        ctx.accounts.token_mint.decimals,
+        token_mint.decimals,
+    )?;
+
+    // Only credit what was received, transfer fee mints withhold part of the transfer
+    allocation_token.reload()?;
+    let received_amount = allocation_token.amount
+        .checked_sub(expected_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let tokens = &mut allocation.tokens;
+    if index == tokens.len() {
+        tokens.push(AllocationToken::default());
+    }
+    let token = &mut tokens[index];
+    if token.mint != token_mint_key {
+        token.mint = token_mint_key;
+        token.claimed = 0;
+        token.token_program = token_program.key();
+    }
+    token.amount = token.amount
+        .checked_add(received_amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok((index, AllocationIncreased {
+        nft_mint: nft_mint.key(),
+        mint: token_mint_key,
+        gross: amount,
+        fee: fee_amount,
+        net: received_amount,
+        excess: excess_amount,
+    }))
+}
+
+pub fn increase_allocation(ctx: Context<IncreaseAllocationState>, amount: u64, wrap: bool) -> Result<()> {
+    // Only mints on the allowlist can be added to an allocation
+    if *ctx.accounts.allowed_mint.owner != id() || ctx.accounts.allowed_mint.data_is_empty() {
+        return Err(RuntimeError::MintNotAllowed.into());
+    }
+
+    // Only the increase authority can add to a restricted allocation
+    if !ctx.accounts.allocation.can_increase(&ctx.accounts.signer.key()) {
+        return Err(RuntimeError::NotIncreaseAuthority.into());
+    }
+
+    // Grow the allocation account if the token needs a new slot
+    let token_mint = ctx.accounts.token_mint.key();
+    CrossProgramInvocations::grow_account(
+        ctx.accounts.allocation.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.allocation.space_with(&token_mint),
+    )?;
+
+    // Wrap the deposit from the signer's lamports into the signer token account
+    if wrap {
+        if token_mint != native_mint::ID {
+            return Err(RuntimeError::NotNativeMint.into());
+        }
+        CrossProgramInvocations::transfer_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            None,
+            amount,
+        )?;
+        CrossProgramInvocations::sync_token(
+            ctx.accounts.signer_token_account.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    let (index, event) = deposit_token(
+        &ctx.accounts.nft_mint,
+        &mut ctx.accounts.allocation,
+        &ctx.accounts.fee_config,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.token_mint,
+        &ctx.accounts.signer_token_account,
+        &mut ctx.accounts.allocation_token_account,
+        &ctx.accounts.fee_token_account,
+        &ctx.accounts.token_program.to_account_info(),
+        amount,
     )?;
 
+    // Close the wrapped signer token account again if it is now empty
+    if wrap {
+        ctx.accounts.signer_token_account.reload()?;
+        if ctx.accounts.signer_token_account.amount == 0 {
+            CrossProgramInvocations::close_token(
+                ctx.accounts.signer_token_account.to_account_info(),
+                ctx.accounts.signer.to_account_info(),
+                ctx.accounts.signer.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                None,
+            )?;
+        }
+    }
+
+    // Keep the nft metadata in sync with the slot
+    let token = ctx.accounts.allocation.tokens[index];
+    sync_slot_metadata(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        &token,
+        index,
+    )?;
+
+    emit_cpi!(event);
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct IncreaseAllocationState<'info> {
     #[account(mut)]
@@ -109,13 +223,15 @@ pub struct IncreaseAllocationState<'info> {
     #[account(
         seeds = [FeeConfigAccount::seed()],
         bump,
-        constraint = fee_config.initialized
+        constraint = fee_config.is_current()
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
     #[account(
+        mut,
         mint::authority = fee_config,
         mint::decimals = 0,
+        mint::token_program = token_extensions_program,
         constraint = nft_mint.supply == 1
     )]
     pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
@@ -123,7 +239,7 @@ pub struct IncreaseAllocationState<'info> {
     #[account(
         mut,
         seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
-        constraint = allocation.initialized,
+        constraint = allocation.is_current(),
         bump
     )]
     pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
@@ -134,11 +250,19 @@ pub struct IncreaseAllocationState<'info> {
     )]
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    /// CHECK: Might not exist if the mint is not allowed, checked in the instruction
     #[account(
-        mut,
+        seeds = [AllowedMintAccount::seed(), token_mint.key().as_ref()],
+        bump
+    )]
+    pub allowed_mint: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
         associated_token::mint = token_mint,
         associated_token::authority = signer,
         associated_token::token_program = token_program,
+        payer = signer
     )]
     pub signer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -164,7 +288,10 @@ pub struct IncreaseAllocationState<'info> {
     pub system_program: Program<'info, System>,
 
     #[account()]
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
 
     #[account()]
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::Mint;
+
+use crate::id;
+use crate::instructions::increase_allocation::deposit_token;
+use crate::state::allocation::AllocationAccount;
+use crate::state::allowlist::AllowedMintAccount;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
+use crate::utility::metadata::sync_slot_metadata;
+use crate::utility::token::find_program;
+
+pub fn increase_allocation_batch<'info>(ctx: Context<'_, '_, 'info, 'info, IncreaseAllocationBatchState<'info>>, amounts: Vec<u64>) -> Result<()> {
+    // Every amount requires its mint, allowed mint, signer, allocation and fee token account
+    if amounts.is_empty() || ctx.remaining_accounts.len() != amounts.len() * 5 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+
+    // Only the increase authority can add to a restricted allocation
+    if !ctx.accounts.allocation.can_increase(&ctx.accounts.signer.key()) {
+        return Err(RuntimeError::NotIncreaseAuthority.into());
+    }
+
+    let programs = [
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    for (amount, accounts) in amounts.iter().zip(ctx.remaining_accounts.chunks(5)) {
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.decimals == 0 {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Only mints on the allowlist can be added to an allocation
+        let allowed_mint = Pubkey::find_program_address(&[AllowedMintAccount::seed(), token_mint.key().as_ref()], &id()).0;
+        if accounts[1].key() != allowed_mint || *accounts[1].owner != id() || accounts[1].data_is_empty() {
+            return Err(RuntimeError::MintNotAllowed.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(&programs, token_mint.to_account_info().owner)?;
+
+        // Create the token accounts if needed, this also verifies the addresses
+        for (account, authority) in accounts[2..].iter().zip([
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.allocation.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+        ]) {
+            CrossProgramInvocations::create_token_account(
+                ctx.accounts.signer.to_account_info(),
+                account.to_account_info(),
+                authority,
+                token_mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                token_program.clone(),
+                ctx.accounts.associated_token_program.to_account_info(),
+            )?;
+        }
+
+        // Grow the allocation account if the token needs a new slot
+        CrossProgramInvocations::grow_account(
+            ctx.accounts.allocation.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.allocation.space_with(&token_mint.key()),
+        )?;
+
+        let (index, event) = deposit_token(
+            &ctx.accounts.nft_mint,
+            &mut ctx.accounts.allocation,
+            &ctx.accounts.fee_config,
+            &ctx.accounts.signer.to_account_info(),
+            &token_mint,
+            &InterfaceAccount::try_from(&accounts[2])?,
+            &mut InterfaceAccount::try_from(&accounts[3])?,
+            &InterfaceAccount::try_from(&accounts[4])?,
+            token_program,
+            *amount,
+        )?;
+
+        // Keep the nft metadata in sync with the slot
+        let token = ctx.accounts.allocation.tokens[index];
+        sync_slot_metadata(
+            ctx.accounts.nft_mint.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            &token,
+            index,
+        )?;
+
+        emit_cpi!(event);
+    }
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct IncreaseAllocationBatchState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+        constraint = nft_mint.supply == 1
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -1,39 +1,54 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::extension::metadata_pointer::MetadataPointer;
 use anchor_spl::token_2022::spl_token_2022::extension::mint_close_authority::MintCloseAuthority;
+use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
 use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
 use anchor_spl::token_2022::spl_token_2022::state::Mint as StateMint;
 use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata;
 use anchor_spl::token_interface::{TokenAccount, Mint};
 
 use crate::id;
-use crate::state::allocation::AllocationAccount;
-use crate::state::fee::FeeConfigAccount;
+use crate::state::allocation::{AllocationAccount, AllocationMetadata, AllocationToken, VestingSchedule};
+use crate::state::event::AllocationInitialized;
+use crate::state::fee::{DefaultMint, FeeConfigAccount};
 use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
 use crate::utility::signer::SeedSigner;
 
-static SOL_MINT: Pubkey = Pubkey::new_from_array([
-    6, 155, 136, 87, 254, 171, 129, 132,
-    251, 104, 127, 99, 70, 24, 192, 53,
-    218, 196, 57, 220, 26, 235, 59, 85,
-    152, 160, 240, 0, 0, 0, 0, 1
-]);
-
-static USDC_MINT: Pubkey = Pubkey::new_from_array([
-    198, 250, 122, 243, 190, 219, 173, 58,
-    61, 101, 243, 106, 171, 201, 116, 49,
-    177, 187, 228, 194, 210, 246, 224, 228,
-    124, 166, 2, 3, 69, 47, 93, 97
-]);
-
-static USDT_MINT: Pubkey = Pubkey::new_from_array([
-    206, 1, 14, 96, 175, 237, 178, 39,
-    23, 189, 99, 25, 47, 84, 20, 90,
-    63, 150, 90, 51, 187, 130, 210, 199,
-    2, 158, 178, 206, 30, 32, 130, 100
-]);
-
-pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, authority: Option<Pubkey>) -> Result<()> {
+pub fn initialize_allocation_account(
+    allocation: &mut AllocationAccount,
+    default_mints: &[DefaultMint],
+    recover_authority: Pubkey,
+    decrease_authority: Pubkey,
+    vesting: Option<VestingSchedule>,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    if vesting.is_some_and(|x| !x.is_valid()) {
+        return Err(RuntimeError::InvalidVestingSchedule.into());
+    }
+    if let Some(timestamp) = expires_at {
+        if timestamp <= Clock::get()?.unix_timestamp {
+            return Err(RuntimeError::InvalidExpiry.into());
+        }
+    }
+
+    allocation.version = AllocationAccount::VERSION;
+    allocation.decrease_authority = decrease_authority;
+    allocation.recover_authority = recover_authority;
+    allocation.vesting = vesting;
+    allocation.expires_at = expires_at;
+    allocation.tokens = default_mints
+        .iter()
+        .map(|x| AllocationToken { mint: x.mint, token_program: x.token_program, ..Default::default() })
+        .collect();
+    allocation.initialized = true;
+
+    Ok(())
+}
+
+pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, authority: Option<Pubkey>, vesting: Option<VestingSchedule>, expires_at: Option<i64>, metadata: Option<AllocationMetadata>) -> Result<()> {
     let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
     let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
 
@@ -45,6 +60,45 @@ pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, authority:
     if mint_close_extension.close_authority.0 != ctx.accounts.fee_config.key() {
         return Err(ProgramError::InvalidAccountData.into());
     }
+
+    // A new allocation must carry metadata owned by the fee config that matches the arguments
+    if ctx.accounts.allocation.initialized {
+        if metadata.is_some() {
+            return Err(ProgramError::InvalidInstructionData.into());
+        }
+    } else {
+        let expected = metadata.ok_or(RuntimeError::InvalidMetadata)?;
+        let nft_mint = ctx.accounts.nft_mint.key();
+        let fee_config = ctx.accounts.fee_config.key();
+
+        // The fee config must be the only one able to freeze or move the nft so recover and freeze keep working
+        let delegate: Option<Pubkey> = mint_state.get_extension::<PermanentDelegate>()?.delegate.into();
+        let freeze_authority: Option<Pubkey> = mint_state.base.freeze_authority.into();
+        if delegate != Some(fee_config) || freeze_authority != Some(fee_config) {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        let pointer = mint_state.get_extension::<MetadataPointer>()?;
+        let pointer_authority: Option<Pubkey> = pointer.authority.into();
+        let pointer_address: Option<Pubkey> = pointer.metadata_address.into();
+        if pointer_address != Some(nft_mint) || pointer_authority.is_some_and(|x| x != fee_config) {
+            return Err(RuntimeError::InvalidMetadata.into());
+        }
+        let token_metadata = mint_state.get_variable_len_extension::<TokenMetadata>()?;
+        let update_authority: Option<Pubkey> = token_metadata.update_authority.into();
+        if update_authority != Some(fee_config)
+            || token_metadata.mint != nft_mint
+            || token_metadata.name != expected.name
+            || token_metadata.symbol != expected.symbol
+            || token_metadata.uri != expected.uri {
+            return Err(RuntimeError::InvalidMetadata.into());
+        }
+
+        // Slot fields are only written by the program so pre-seeded ones could fake balances
+        if token_metadata.additional_metadata.iter().any(|(key, _)| key.starts_with("slot")) {
+            return Err(RuntimeError::InvalidMetadata.into());
+        }
+    }
     drop(mint_data);
 
     // Mint the nft to the signer if not already minted
@@ -75,19 +129,33 @@ pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, authority:
         ctx.accounts.allocation.decrease_authority = authority.unwrap_or_default();
     }
 
+    // The vesting schedule and expiry can only be set when creating the allocation
+    if ctx.accounts.allocation.initialized && (vesting.is_some() || expires_at.is_some()) {
+        return Err(ProgramError::InvalidInstructionData.into());
+    }
+
     // Initialize the allocation account if not initalized
     if !ctx.accounts.allocation.initialized {
-        ctx.accounts.allocation.decrease_authority = authority.unwrap_or_default();
-        ctx.accounts.allocation.recover_authority = ctx.accounts.signer.key();
-        ctx.accounts.allocation.first_token_mint = SOL_MINT;
-        ctx.accounts.allocation.second_token_mint = USDC_MINT;
-        ctx.accounts.allocation.third_token_mint = USDT_MINT;
-        ctx.accounts.allocation.initialized = true;
+        initialize_allocation_account(
+            &mut ctx.accounts.allocation,
+            &ctx.accounts.fee_config.default_mints,
+            ctx.accounts.signer.key(),
+            authority.unwrap_or_default(),
+            vesting,
+            expires_at,
+        )?;
     }
 
+    emit_cpi!(AllocationInitialized {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        decrease_authority: ctx.accounts.allocation.decrease_authority,
+        recover_authority: ctx.accounts.allocation.recover_authority,
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeAllocationState<'info> {
     #[account(
@@ -99,7 +167,7 @@ pub struct InitializeAllocationState<'info> {
     #[account(
         seeds = [FeeConfigAccount::seed()],
         bump,
-        constraint = fee_config.initialized
+        constraint = fee_config.is_current()
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
@@ -124,9 +192,10 @@ pub struct InitializeAllocationState<'info> {
     #[account(
         init_if_needed,
         seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
-        space = AllocationAccount::space(),
+        space = AllocationAccount::space_for(allocation, fee_config.default_mints.len()),
         payer = signer,
         bump,
+        constraint = allocation.version != AllocationAccount::LEGACY_VERSION
     )]
     pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
 
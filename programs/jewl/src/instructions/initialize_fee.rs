@@ -1,15 +1,21 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
-use crate::state::fee::FeeConfigAccount;
+use crate::state::event::FeeConfigUpdated;
+use crate::state::fee::{DefaultMint, FeeConfigAccount};
+use crate::utility::cpi::CrossProgramInvocations;
 
-pub fn initialize_fee(ctx: Context<InitializeFeeState>, fee_bps: Option<u16>, fee_authority: Option<Pubkey>, fee_withdraw_authority: Option<Pubkey>) -> Result<()> {
+pub fn initialize_fee<'info>(ctx: Context<'_, '_, 'info, 'info, InitializeFeeState<'info>>, fee_bps: Option<u16>, fee_authority: Option<Pubkey>, fee_withdraw_authority: Option<Pubkey>, max_tokens: Option<u8>, default_mints: Option<Vec<Pubkey>>) -> Result<()> {
     // If not initialized set the defaults first
     if !ctx.accounts.fee_config.initialized {
+        ctx.accounts.fee_config.version = FeeConfigAccount::VERSION;
         ctx.accounts.fee_config.fee_authority = *ctx.accounts.signer.key;
         ctx.accounts.fee_config.fee_withdraw_authority = *ctx.accounts.signer.key;
         ctx.accounts.fee_config.fee_bps = 100;
+        ctx.accounts.fee_config.max_tokens = FeeConfigAccount::DEFAULT_MAX_TOKENS;
+        ctx.accounts.fee_config.default_mints = Vec::new();
         ctx.accounts.fee_config.initialized = true;
-    } else if fee_bps.is_none() && fee_authority.is_none() && fee_withdraw_authority.is_none() {
+    } else if fee_bps.is_none() && fee_authority.is_none() && fee_withdraw_authority.is_none() && max_tokens.is_none() && default_mints.is_none() {
         return Err(ProgramError::InvalidInstructionData.into());
     }
 
@@ -26,9 +32,53 @@ pub fn initialize_fee(ctx: Context<InitializeFeeState>, fee_bps: Option<u16>, fe
         ctx.accounts.fee_config.fee_bps = new_fee_bps;
     }
 
+    if let Some(new_max_tokens) = max_tokens {
+        if new_max_tokens > FeeConfigAccount::MAX_TOKENS {
+            return Err(ProgramError::InvalidInstructionData.into());
+        }
+        ctx.accounts.fee_config.max_tokens = new_max_tokens;
+    }
+
+    // Every default mint must be passed as a remaining account to find its token program
+    if let Some(new_default_mints) = default_mints {
+        if ctx.remaining_accounts.len() != new_default_mints.len() {
+            return Err(ProgramError::NotEnoughAccountKeys.into());
+        }
+        let mut mints = Vec::with_capacity(new_default_mints.len());
+        for (mint, account) in new_default_mints.iter().zip(ctx.remaining_accounts) {
+            InterfaceAccount::<Mint>::try_from(account)?;
+            if account.key() != *mint || mints.iter().any(|x: &DefaultMint| x.mint == *mint) {
+                return Err(ProgramError::InvalidAccountData.into());
+            }
+            mints.push(DefaultMint { mint: *mint, token_program: *account.owner });
+        }
+
+        CrossProgramInvocations::grow_account(
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            FeeConfigAccount::space(mints.len()),
+        )?;
+        ctx.accounts.fee_config.default_mints = mints;
+    }
+
+    // Every new allocation must be able to hold the default mints
+    if ctx.accounts.fee_config.default_mints.len() > usize::from(ctx.accounts.fee_config.max_tokens) {
+        return Err(ProgramError::InvalidInstructionData.into());
+    }
+
+    emit_cpi!(FeeConfigUpdated {
+        fee_authority: ctx.accounts.fee_config.fee_authority,
+        fee_withdraw_authority: ctx.accounts.fee_config.fee_withdraw_authority,
+        fee_bps: ctx.accounts.fee_config.fee_bps,
+        max_tokens: ctx.accounts.fee_config.max_tokens,
+        default_mints: ctx.accounts.fee_config.default_mints.iter().map(|x| x.mint).collect(),
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeFeeState<'info> {
     #[account(
@@ -41,8 +91,9 @@ pub struct InitializeFeeState<'info> {
         init_if_needed,
         seeds = [FeeConfigAccount::seed()],
         bump,
-        space = FeeConfigAccount::space(),
+        space = FeeConfigAccount::space_for(fee_config),
         payer = signer,
+        constraint = fee_config.version != FeeConfigAccount::LEGACY_VERSION
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
@@ -0,0 +1,313 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::state::allocation::{AllocationAccount, AllocationToken};
+use crate::state::event::AllocationsMerged;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
+use crate::utility::metadata::sync_all_slot_metadata;
+use crate::utility::signer::SeedSigner;
+use crate::utility::token::{find_program, has_withheld_fees, is_soulbound};
+
+pub fn merge_allocations<'info>(ctx: Context<'_, '_, 'info, 'info, MergeAllocationsState<'info>>) -> Result<()> {
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let fee_signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+    let source_mint = ctx.accounts.source_nft_mint.key();
+    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), source_mint.as_ref()], &id()).1;
+    let source_signer: SeedSigner = &[&[AllocationAccount::seed(), source_mint.as_ref(), &[bump]]];
+
+    // Every source token requires its mint, source, target and fee token account
+    let tokens = ctx.accounts.source_allocation.tokens.clone();
+    if ctx.remaining_accounts.len() < tokens.len() * 4 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+    let (token_accounts, authority_accounts) = ctx.remaining_accounts.split_at(tokens.len() * 4);
+
+    // Merging must not unlock tokens earlier than either allocation would
+    let source = &ctx.accounts.source_allocation;
+    let target = &ctx.accounts.allocation;
+    if source.vesting != target.vesting || source.expires_at != target.expires_at {
+        return Err(RuntimeError::AllocationMismatch.into());
+    }
+
+    // A soulbound allocation must not become transferable by merging it into a transferable one
+    if is_soulbound(&ctx.accounts.source_nft_mint.to_account_info())? && !is_soulbound(&ctx.accounts.nft_mint.to_account_info())? {
+        return Err(RuntimeError::SoulboundMismatch.into());
+    }
+
+    // Different authorities can only be merged if both authorities sign, the remaining accounts after the tokens are extra signers
+    let signer = ctx.accounts.signer.key();
+    let has_signed = |authority: &Pubkey| *authority == signer || authority_accounts.iter().any(|x| x.is_signer && x.key == authority);
+    for (source_authority, target_authority) in [
+        (source.decrease_authority, target.decrease_authority),
+        (source.recover_authority, target.recover_authority),
+    ] {
+        if source_authority != target_authority && !(has_signed(&source_authority) && has_signed(&target_authority)) {
+            return Err(ProgramError::MissingRequiredSignature.into());
+        }
+    }
+
+    // An allocation without an increase authority is open to anyone so only the set increase authorities have to sign
+    let increase_authorities = [source.increase_authority, target.increase_authority];
+    if source.increase_authority != target.increase_authority && !increase_authorities.iter().flatten().all(has_signed) {
+        return Err(ProgramError::MissingRequiredSignature.into());
+    }
+
+    // Move each token from the source allocation to the target allocation
+    let max_tokens = usize::from(ctx.accounts.fee_config.max_tokens);
+    let programs = [
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    for (token, accounts) in tokens.iter().zip(token_accounts.chunks(4)) {
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.key() != token.mint || *token_mint.to_account_info().owner != token.token_program {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(&programs, &token.token_program)?;
+
+        // Create the token accounts if needed, this also verifies the addresses
+        for (account, authority) in [&accounts[1], &accounts[3]].into_iter().zip([
+            ctx.accounts.source_allocation.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+        ]) {
+            CrossProgramInvocations::create_token_account(
+                ctx.accounts.signer.to_account_info(),
+                account.to_account_info(),
+                authority,
+                token_mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                token_program.clone(),
+                ctx.accounts.associated_token_program.to_account_info(),
+            )?;
+        }
+        let source_token = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+
+        // If there are more tokens than the slot holds, transfer the difference to the fee account
+        if source_token.amount > token.amount {
+            let transfer_amount = source_token.amount
+                .checked_sub(token.amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            CrossProgramInvocations::transfer_token(
+                source_token.to_account_info(),
+                token_mint.to_account_info(),
+                accounts[3].to_account_info(),
+                ctx.accounts.source_allocation.to_account_info(),
+                token_program.clone(),
+                Some(source_signer),
+                transfer_amount,
+                token_mint.decimals,
+            )?;
+        }
+
+        if token.amount > 0 {
+            // Find the slot for the token, either the existing one, an empty one or a new one
+            let target_tokens = &ctx.accounts.allocation.tokens;
+            let index = if let Some(index) = target_tokens.iter().position(|x| x.mint == token.mint) {
+                index
+            } else if ctx.accounts.allocation.locked {
+                return Err(RuntimeError::AllocationLocked.into());
+            } else if let Some(index) = target_tokens.iter().position(|x| x.amount == 0) {
+                index
+            } else if target_tokens.len() < max_tokens {
+                target_tokens.len()
+            } else {
+                return Err(RuntimeError::MaxTokensReached.into());
+            };
+
+            CrossProgramInvocations::create_token_account(
+                ctx.accounts.signer.to_account_info(),
+                accounts[2].to_account_info(),
+                ctx.accounts.allocation.to_account_info(),
+                token_mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                token_program.clone(),
+                ctx.accounts.associated_token_program.to_account_info(),
+            )?;
+            let expected_amount = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?.amount;
+            CrossProgramInvocations::transfer_token(
+                source_token.to_account_info(),
+                token_mint.to_account_info(),
+                accounts[2].to_account_info(),
+                ctx.accounts.source_allocation.to_account_info(),
+                token_program.clone(),
+                Some(source_signer),
+                token.amount,
+                token_mint.decimals,
+            )?;
+
+            // Only credit what was received, transfer fee mints withhold part of the transfer
+            let received_amount = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?.amount
+                .checked_sub(expected_amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+
+            // Grow the allocation account if the token needs a new slot
+            if index == ctx.accounts.allocation.tokens.len() {
+                CrossProgramInvocations::grow_account(
+                    ctx.accounts.allocation.to_account_info(),
+                    ctx.accounts.signer.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    AllocationAccount::space(index + 1),
+                )?;
+                ctx.accounts.allocation.tokens.push(AllocationToken::default());
+            }
+            let target_token = &mut ctx.accounts.allocation.tokens[index];
+            if target_token.mint != token.mint {
+                target_token.mint = token.mint;
+                target_token.claimed = 0;
+                target_token.token_program = token.token_program;
+            }
+            target_token.amount = target_token.amount
+                .checked_add(received_amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            target_token.claimed = target_token.claimed
+                .checked_add(token.claimed)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+
+        // Close the source token account and reclaim rent unless it still holds withheld transfer fees
+        if !has_withheld_fees(&source_token.to_account_info())? {
+            CrossProgramInvocations::close_token(
+                source_token.to_account_info(),
+                ctx.accounts.signer.to_account_info(),
+                ctx.accounts.source_allocation.to_account_info(),
+                token_program.clone(),
+                Some(source_signer),
+            )?;
+        }
+    }
+
+    // Keep the nft metadata in sync with the merged slots
+    sync_all_slot_metadata(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        &ctx.accounts.allocation.tokens,
+    )?;
+
+    // Burn the source allocation nft
+    CrossProgramInvocations::burn_token(
+        ctx.accounts.source_nft_mint.to_account_info(),
+        ctx.accounts.source_nft_token.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        None,
+        1,
+    )?;
+
+    // Close the source nft token account and reclaim rent
+    CrossProgramInvocations::close_token(
+        ctx.accounts.source_nft_token.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        None,
+    )?;
+
+    // Close the source nft token mint and reclaim rent
+    CrossProgramInvocations::close_token(
+        ctx.accounts.source_nft_mint.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(fee_signer),
+    )?;
+
+    // Close the source allocation account and reclaim rent
+    ctx.accounts.source_allocation
+        .close(ctx.accounts.signer.to_account_info())?;
+
+    emit_cpi!(AllocationsMerged {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        source_nft_mint: source_mint,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MergeAllocationsState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        constraint = nft_token.amount == 1,
+        constraint = !nft_token.is_frozen()
+    )]
+    pub nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+        constraint = source_nft_mint.key() != nft_mint.key()
+    )]
+    pub source_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = source_nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        constraint = source_nft_token.amount == 1,
+        constraint = !source_nft_token.is_frozen()
+    )]
+    pub source_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), source_nft_mint.key().as_ref()],
+        constraint = source_allocation.is_current(),
+        bump
+    )]
+    pub source_allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
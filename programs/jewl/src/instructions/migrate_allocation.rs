@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::state::allocation::{AllocationAccount, LegacyAllocationAccount};
+use crate::state::event::AllocationMigrated;
+use crate::utility::cpi::CrossProgramInvocations;
+
+pub fn migrate_allocation(ctx: Context<MigrateAllocationState>) -> Result<()> {
+    let info = ctx.accounts.allocation.to_account_info();
+
+    // Allocations already in the current layout are left untouched
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        if data.get(..8) != Some(AllocationAccount::DISCRIMINATOR.as_ref()) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        match data.get(8).copied() {
+            Some(AllocationAccount::VERSION) => return Ok(()),
+            Some(AllocationAccount::LEGACY_VERSION) => LegacyAllocationAccount::deserialize(&mut &data[8..])?,
+            _ => return Err(ProgramError::InvalidAccountData.into()),
+        }
+    };
+
+    // The new layout holds every legacy field and more so the account only grows
+    let allocation = AllocationAccount::from(legacy);
+    CrossProgramInvocations::grow_account(
+        info.clone(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        AllocationAccount::space(allocation.tokens.len()),
+    )?;
+
+    allocation.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit_cpi!(AllocationMigrated {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        from_version: AllocationAccount::LEGACY_VERSION,
+        to_version: allocation.version,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Only used to derive the allocation address
+    #[account()]
+    pub nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Can be in a legacy layout so it is deserialized in the instruction
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub allocation: UncheckedAccount<'info>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::state::event::FeeConfigMigrated;
+use crate::state::fee::{FeeConfigAccount, LegacyFeeConfigAccount};
+use crate::utility::cpi::CrossProgramInvocations;
+
+pub fn migrate_fee_config(ctx: Context<MigrateFeeConfigState>) -> Result<()> {
+    let info = ctx.accounts.fee_config.to_account_info();
+
+    // A fee config already in the current layout is left untouched
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        if data.get(..8) != Some(FeeConfigAccount::DISCRIMINATOR.as_ref()) {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        match data.get(8).copied() {
+            Some(FeeConfigAccount::VERSION) => return Ok(()),
+            Some(FeeConfigAccount::LEGACY_VERSION) => LegacyFeeConfigAccount::deserialize(&mut &data[8..])?,
+            _ => return Err(ProgramError::InvalidAccountData.into()),
+        }
+    };
+
+    // The new layout holds every legacy field and more so the account only grows
+    let fee_config = FeeConfigAccount::from(legacy);
+    CrossProgramInvocations::grow_account(
+        info.clone(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        FeeConfigAccount::space(fee_config.default_mints.len()),
+    )?;
+
+    fee_config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit_cpi!(FeeConfigMigrated {
+        from_version: FeeConfigAccount::LEGACY_VERSION,
+        to_version: fee_config.version,
+        fee_authority: fee_config.fee_authority,
+        fee_withdraw_authority: fee_config.fee_withdraw_authority,
+        fee_bps: fee_config.fee_bps,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateFeeConfigState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Can be in a legacy layout so it is deserialized in the instruction
+    #[account(
+        mut,
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        owner = crate::ID
+    )]
+    pub fee_config: UncheckedAccount<'info>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+}
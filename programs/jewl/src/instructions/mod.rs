@@ -1,6 +1,27 @@
+pub mod add_allowed_mint;
+pub mod cancel_escrow;
+pub mod claim_allocation;
+pub mod claim_vested;
+pub mod create_allocation;
 pub mod decrease_allocation;
+pub mod escrow_allocation;
 pub mod exercise_allocation;
+pub mod exercise_allocation_batch;
+pub mod freeze_allocation;
 pub mod increase_allocation;
+pub mod increase_allocation_batch;
 pub mod initialize_allocation;
 pub mod initialize_fee;
+pub mod merge_allocations;
+pub mod migrate_allocation;
+pub mod migrate_fee_config;
+pub mod reclaim_expired_allocation;
+pub mod recover_allocation_nft;
+pub mod remove_allowed_mint;
+pub mod remove_slot;
+pub mod split_allocation;
+pub mod thaw_allocation;
+pub mod update_allocation_access;
 pub mod withdraw_fee;
+pub mod withdraw_fees_batch;
+pub mod withdraw_from_allocation;
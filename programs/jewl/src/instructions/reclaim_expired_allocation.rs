@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::instructions::exercise_allocation::withdraw_token;
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::{AllocationReclaimed, ExercisedToken};
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::error::RuntimeError;
+use crate::utility::signer::SeedSigner;
+use crate::utility::token::{find_program, is_permanent_delegate};
+
+pub fn reclaim_expired_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, ReclaimExpiredAllocationState<'info>>) -> Result<()> {
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let fee_signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+
+    // Only an allocation that has passed its expiry can be reclaimed
+    let expires_at = ctx.accounts.allocation.expires_at
+        .ok_or(RuntimeError::AllocationNotExpired)?;
+    if Clock::get()?.unix_timestamp < expires_at {
+        return Err(RuntimeError::AllocationNotExpired.into());
+    }
+
+    // Every token requires its mint, signer, allocation and fee token account
+    let tokens = ctx.accounts.allocation.tokens.clone();
+    if ctx.remaining_accounts.len() != tokens.len() * 4 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+
+    // Withdraw each token to the recover authority
+    let mut reclaimed = Vec::with_capacity(tokens.len());
+    let programs = [
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    for (token, accounts) in tokens.iter().zip(ctx.remaining_accounts.chunks(4)) {
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.key() != token.mint || *token_mint.to_account_info().owner != token.token_program {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(&programs, &token.token_program)?;
+
+        // Create the token accounts if needed, this also verifies the addresses
+        for (account, authority) in accounts[1..].iter().zip([
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.allocation.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+        ]) {
+            CrossProgramInvocations::create_token_account(
+                ctx.accounts.signer.to_account_info(),
+                account.to_account_info(),
+                authority,
+                token_mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                token_program.to_account_info(),
+                ctx.accounts.associated_token_program.to_account_info(),
+            )?;
+        }
+
+        let excess = withdraw_token(
+            &ctx.accounts.nft_mint,
+            &InterfaceAccount::try_from(&accounts[1])?,
+            &InterfaceAccount::try_from(&accounts[2])?,
+            &InterfaceAccount::try_from(&accounts[3])?,
+            &ctx.accounts.allocation,
+            &token_mint,
+            token_program,
+            token.amount,
+        )?;
+        reclaimed.push(ExercisedToken {
+            mint: token.mint,
+            amount: token.amount,
+            excess,
+        });
+    }
+
+    // The nft can only be burned if it still exists and the fee config is its permanent delegate
+    let nft_mint_info = ctx.accounts.nft_mint.to_account_info();
+    let burnable = ctx.accounts.nft_mint.supply > 0
+        && is_permanent_delegate(&nft_mint_info, &ctx.accounts.fee_config.key())?;
+    if burnable {
+        let nft_token = ctx.accounts.nft_token.as_ref()
+            .ok_or(ErrorCode::AccountNotEnoughKeys)?;
+        if nft_token.amount != 1 {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // A frozen nft account has to be thawed before it can be burned
+        if nft_token.is_frozen() {
+            CrossProgramInvocations::thaw_token(
+                nft_token.to_account_info(),
+                nft_mint_info.to_account_info(),
+                ctx.accounts.fee_config.to_account_info(),
+                ctx.accounts.token_extensions_program.to_account_info(),
+                Some(fee_signer),
+            )?;
+        }
+
+        // Burn the nft from its holder using the permanent delegate so it no longer advertises the reclaimed slots
+        CrossProgramInvocations::burn_token(
+            nft_mint_info.to_account_info(),
+            nft_token.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            Some(fee_signer),
+            1,
+        )?;
+    }
+
+    // Close the nft token mint and reclaim rent, a mint can only be closed once its supply is zero
+    if burnable || ctx.accounts.nft_mint.supply == 0 {
+        CrossProgramInvocations::close_token(
+            nft_mint_info.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            Some(fee_signer),
+        )?;
+    }
+
+    // Close the allocation account and reclaim rent, the nft can no longer be initialized or exercised
+    ctx.accounts.allocation
+        .close(ctx.accounts.signer.to_account_info())?;
+
+    emit_cpi!(AllocationReclaimed {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        owner: ctx.accounts.nft_token.as_ref().map(|x| x.owner),
+        tokens: reclaimed,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReclaimExpiredAllocationState<'info> {
+    #[account(
+        mut,
+        constraint = signer.key() == allocation.recover_authority.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_extensions_program,
+    )]
+    pub nft_token: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
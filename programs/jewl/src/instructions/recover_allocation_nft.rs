@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::AllocationNftRecovered;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::signer::SeedSigner;
+
+pub fn recover_allocation_nft(ctx: Context<RecoverAllocationNftState>) -> Result<()> {
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+
+    // A frozen nft account has to be thawed before it can be burned
+    if ctx.accounts.old_nft_token.is_frozen() {
+        CrossProgramInvocations::thaw_token(
+            ctx.accounts.old_nft_token.to_account_info(),
+            ctx.accounts.nft_mint.to_account_info(),
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            Some(signer),
+        )?;
+    }
+
+    // Burn the nft from the old holder using the permanent delegate
+    CrossProgramInvocations::burn_token(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.old_nft_token.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+        1,
+    )?;
+
+    // Mint a replacement nft to the new holder
+    CrossProgramInvocations::mint_token(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.new_nft_token.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        Some(signer),
+        1,
+    )?;
+
+    emit_cpi!(AllocationNftRecovered {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        old_owner: ctx.accounts.old_nft_token.owner,
+        new_owner: ctx.accounts.new_owner.key(),
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RecoverAllocationNftState<'info> {
+    #[account(
+        mut,
+        constraint = signer.key() == allocation.recover_authority.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::token_program = token_extensions_program,
+        constraint = old_nft_token.amount == 1
+    )]
+    pub old_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Any wallet can receive the replacement nft
+    #[account()]
+    pub new_owner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = nft_mint,
+        associated_token::authority = new_owner,
+        associated_token::token_program = token_extensions_program,
+        payer = signer
+    )]
+    pub new_nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
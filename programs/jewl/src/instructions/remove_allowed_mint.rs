@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::state::allowlist::AllowedMintAccount;
+use crate::state::event::AllowedMintRemoved;
+use crate::state::fee::FeeConfigAccount;
+
+pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMintState>) -> Result<()> {
+    let mint = ctx.accounts.allowed_mint.mint;
+
+    // Close the allowed mint account and reclaim rent
+    ctx.accounts.allowed_mint
+        .close(ctx.accounts.signer.to_account_info())?;
+
+    emit_cpi!(AllowedMintRemoved {
+        mint,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveAllowedMintState<'info> {
+    #[account(
+        mut,
+        constraint = fee_config.fee_authority.key() == signer.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        seeds = [AllowedMintAccount::seed(), allowed_mint.mint.as_ref()],
+        constraint = allowed_mint.initialized,
+        bump
+    )]
+    pub allowed_mint: Box<InterfaceAccount<'info, AllowedMintAccount>>,
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::SlotRemoved;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::error::RuntimeError;
+use crate::utility::metadata::sync_all_slot_metadata;
+
+pub fn remove_slot(ctx: Context<RemoveSlotState>, mint: Pubkey) -> Result<()> {
+    // Only empty slots can be removed so no tokens are orphaned
+    let tokens = &mut ctx.accounts.allocation.tokens;
+    let index = tokens
+        .iter()
+        .position(|x| x.mint == mint)
+        .ok_or(RuntimeError::TokenNotFound)?;
+    if tokens[index].amount > 0 {
+        return Err(RuntimeError::SlotNotEmpty.into());
+    }
+    tokens.remove(index);
+
+    // Later slots shift down so rewrite them and drop the last slot field
+    sync_all_slot_metadata(
+        ctx.accounts.nft_mint.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        &ctx.accounts.allocation.tokens,
+    )?;
+
+    emit_cpi!(SlotRemoved {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        mint,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveSlotState<'info> {
+    #[account(
+        mut,
+        constraint = signer.key() == allocation.recover_authority.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+}
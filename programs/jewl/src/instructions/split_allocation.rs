@@ -0,0 +1,212 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::id;
+use crate::instructions::create_allocation::create_nft;
+use crate::state::allocation::{AllocationAccount, AllocationMetadata, AllocationToken};
+use crate::state::event::AllocationSplit;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::metadata::sync_all_slot_metadata;
+use crate::utility::signer::SeedSigner;
+use crate::utility::token::{find_program, is_soulbound};
+
+pub fn split_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, SplitAllocationState<'info>>, nonce: u64, amounts: Vec<u64>, metadata: AllocationMetadata) -> Result<()> {
+    // Every slot requires an amount and its mint, allocation and new allocation token account
+    let tokens = ctx.accounts.allocation.tokens.clone();
+    if amounts.len() != tokens.len() {
+        return Err(ProgramError::InvalidInstructionData.into());
+    }
+    if ctx.remaining_accounts.len() != tokens.len() * 3 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+
+    // The new nft is soulbound if the original one is
+    let soulbound = is_soulbound(&ctx.accounts.nft_mint.to_account_info())?;
+
+    // Create the nft for the new allocation
+    let holder = ctx.accounts.signer.key();
+    let nonce_bytes = nonce.to_le_bytes();
+    let mint_signer: SeedSigner = &[&[AllocationAccount::mint_seed(), holder.as_ref(), nonce_bytes.as_ref(), &[ctx.bumps.new_nft_mint]]];
+    create_nft(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.fee_config.to_account_info(),
+        ctx.accounts.new_nft_mint.to_account_info(),
+        ctx.accounts.new_nft_token.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+        ctx.accounts.associated_token_program.to_account_info(),
+        mint_signer,
+        metadata,
+        soulbound,
+    )?;
+
+    let mint = ctx.accounts.nft_mint.key();
+    let bump = Pubkey::find_program_address(&[AllocationAccount::seed(), mint.as_ref()], &id()).1;
+    let signer: SeedSigner = &[&[AllocationAccount::seed(), mint.as_ref(), &[bump]]];
+
+    // Move the chosen amount of each token to the new allocation
+    let mut new_tokens = Vec::with_capacity(tokens.len());
+    let programs = [
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    for (index, (token, accounts)) in tokens.iter().zip(ctx.remaining_accounts.chunks(3)).enumerate() {
+        let amount = amounts[index];
+        let mut new_token = AllocationToken { mint: token.mint, token_program: token.token_program, ..Default::default() };
+        if amount == 0 {
+            new_tokens.push(new_token);
+            continue;
+        }
+
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.key() != token.mint || *token_mint.to_account_info().owner != token.token_program {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(&programs, &token.token_program)?;
+
+        // Create the token account if needed, this also verifies the address
+        CrossProgramInvocations::create_token_account(
+            ctx.accounts.signer.to_account_info(),
+            accounts[2].to_account_info(),
+            ctx.accounts.new_allocation.to_account_info(),
+            token_mint.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            token_program.clone(),
+            ctx.accounts.associated_token_program.to_account_info(),
+        )?;
+        let allocation_token = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        if allocation_token.owner != ctx.accounts.allocation.key() || allocation_token.mint != token.mint {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        ctx.accounts.allocation.tokens[index].amount = token.amount
+            .checked_sub(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        CrossProgramInvocations::transfer_token(
+            allocation_token.to_account_info(),
+            token_mint.to_account_info(),
+            accounts[2].to_account_info(),
+            ctx.accounts.allocation.to_account_info(),
+            token_program.clone(),
+            Some(signer),
+            amount,
+            token_mint.decimals,
+        )?;
+
+        // Only credit what was received, transfer fee mints withhold part of the transfer
+        new_token.amount = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?.amount;
+        new_tokens.push(new_token);
+    }
+
+    ctx.accounts.new_allocation.version = AllocationAccount::VERSION;
+    ctx.accounts.new_allocation.decrease_authority = ctx.accounts.allocation.decrease_authority;
+    ctx.accounts.new_allocation.recover_authority = ctx.accounts.allocation.recover_authority;
+    ctx.accounts.new_allocation.vesting = ctx.accounts.allocation.vesting;
+    ctx.accounts.new_allocation.expires_at = ctx.accounts.allocation.expires_at;
+    ctx.accounts.new_allocation.increase_authority = ctx.accounts.allocation.increase_authority;
+    ctx.accounts.new_allocation.locked = ctx.accounts.allocation.locked;
+    ctx.accounts.new_allocation.tokens = new_tokens;
+    ctx.accounts.new_allocation.initialized = true;
+
+    // Keep the metadata of both nfts in sync with the moved slots
+    for (nft_mint, allocation) in [
+        (ctx.accounts.nft_mint.to_account_info(), &ctx.accounts.allocation),
+        (ctx.accounts.new_nft_mint.to_account_info(), &ctx.accounts.new_allocation),
+    ] {
+        sync_all_slot_metadata(
+            nft_mint,
+            ctx.accounts.fee_config.to_account_info(),
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_extensions_program.to_account_info(),
+            &allocation.tokens,
+        )?;
+    }
+
+    emit_cpi!(AllocationSplit {
+        nft_mint: mint,
+        new_nft_mint: ctx.accounts.new_nft_mint.key(),
+        amounts,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct SplitAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        constraint = nft_token.amount == 1,
+        constraint = !nft_token.is_frozen()
+    )]
+    pub nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    /// CHECK: Created and initialized in the instruction
+    #[account(
+        mut,
+        seeds = [AllocationAccount::mint_seed(), signer.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_nft_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the instruction by the associated token program
+    #[account(mut)]
+    pub new_nft_token: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [AllocationAccount::seed(), new_nft_mint.key().as_ref()],
+        space = AllocationAccount::space(allocation.tokens.len()),
+        payer = signer,
+        bump,
+    )]
+    pub new_allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
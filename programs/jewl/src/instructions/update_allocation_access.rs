@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::Mint;
+
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::AllocationAccessUpdated;
+use crate::state::fee::FeeConfigAccount;
+
+pub fn update_allocation_access(ctx: Context<UpdateAllocationAccessState>, locked: bool, increase_authority: Option<Pubkey>) -> Result<()> {
+    // Restrict who can increase the allocation and whether new tokens can be added
+    ctx.accounts.allocation.locked = locked;
+    ctx.accounts.allocation.increase_authority = increase_authority;
+
+    emit_cpi!(AllocationAccessUpdated {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        locked,
+        increase_authority,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateAllocationAccessState<'info> {
+    #[account(
+        constraint = signer.key() == allocation.recover_authority.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+}
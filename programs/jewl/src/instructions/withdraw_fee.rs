@@ -1,44 +1,75 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::Token;
-use anchor_spl::token_interface::{TokenAccount, Mint};
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface};
 
 use crate::id;
+use crate::state::event::FeeWithdrawn;
 use crate::state::fee::FeeConfigAccount;
 use crate::utility::cpi::CrossProgramInvocations;
 use crate::utility::signer::SeedSigner;
+use crate::utility::token::has_withheld_fees;
 
-pub fn withdraw_fee(ctx: Context<WithdrawFeeState>, amount: Option<u64>) -> Result<()> {
+// Transfer fees to the destination and close the fee token account once it is empty
+pub fn withdraw_fee_token<'info>(
+    fee_config: &InterfaceAccount<'info, FeeConfigAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    fee_token: &InterfaceAccount<'info, TokenAccount>,
+    destination_token: &InterfaceAccount<'info, TokenAccount>,
+    signer: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: Option<u64>,
+) -> Result<FeeWithdrawn> {
     let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
-    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+    let fee_signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
 
-    // Transfer the fee token to the signer's token account
-    let transfer_amount = amount.unwrap_or(ctx.accounts.fee_token_account.amount);
+    // Transfer the fee token to the destination token account
+    let transfer_amount = amount.unwrap_or(fee_token.amount);
     CrossProgramInvocations::transfer_token(
-        ctx.accounts.fee_token_account.to_account_info(),
-        ctx.accounts.token_mint.to_account_info(),
-        ctx.accounts.signer_token_account.to_account_info(),
-        ctx.accounts.fee_config.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        Some(signer),
+        fee_token.to_account_info(),
+        token_mint.to_account_info(),
+        destination_token.to_account_info(),
+        fee_config.to_account_info(),
+        token_program.clone(),
+        Some(fee_signer),
         transfer_amount,
-        ctx.accounts.token_mint.decimals,
+        token_mint.decimals,
     )?;
 
-    // Close the fee token account if the full amount was withdrawn
-    if transfer_amount == ctx.accounts.fee_token_account.amount {
+    // Close the fee token account if the full amount was withdrawn and it holds no withheld transfer fees
+    if transfer_amount == fee_token.amount && !has_withheld_fees(&fee_token.to_account_info())? {
         CrossProgramInvocations::close_token(
-            ctx.accounts.fee_token_account.to_account_info(),
-            ctx.accounts.signer.to_account_info(),
-            ctx.accounts.fee_config.to_account_info(),
-            ctx.accounts.token_program.to_account_info(),
-            Some(signer),
+            fee_token.to_account_info(),
+            signer.clone(),
+            fee_config.to_account_info(),
+            token_program.clone(),
+            Some(fee_signer),
         )?;
     }
 
+    Ok(FeeWithdrawn {
+        mint: token_mint.key(),
+        amount: transfer_amount,
+        destination: destination_token.key(),
+    })
+}
+
+pub fn withdraw_fee(ctx: Context<WithdrawFeeState>, amount: Option<u64>) -> Result<()> {
+    let event = withdraw_fee_token(
+        &ctx.accounts.fee_config,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.fee_token_account,
+        &ctx.accounts.signer_token_account,
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        amount,
+    )?;
+
+    emit_cpi!(event);
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct WithdrawFeeState<'info> {
     #[account(
@@ -50,7 +81,7 @@ pub struct WithdrawFeeState<'info> {
     #[account(
         seeds = [FeeConfigAccount::seed()],
         bump,
-        constraint = fee_config.initialized
+        constraint = fee_config.is_current()
     )]
     pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
 
@@ -81,7 +112,7 @@ pub struct WithdrawFeeState<'info> {
     pub system_program: Program<'info, System>,
 
     #[account()]
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 
     #[account()]
     pub associated_token_program: Program<'info, AssociatedToken>,
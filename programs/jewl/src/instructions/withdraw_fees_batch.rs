@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint};
+
+use crate::instructions::withdraw_fee::withdraw_fee_token;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::token::find_program;
+
+pub fn withdraw_fees_batch<'info>(ctx: Context<'_, '_, 'info, 'info, WithdrawFeesBatchState<'info>>, amounts: Vec<Option<u64>>) -> Result<()> {
+    // Every amount requires its mint, fee and destination token account
+    if amounts.is_empty() || ctx.remaining_accounts.len() != amounts.len() * 3 {
+        return Err(ProgramError::NotEnoughAccountKeys.into());
+    }
+
+    let programs = [
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    for (amount, accounts) in amounts.iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts[0])?;
+        if token_mint.decimals == 0 {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Each token can be owned by either token program
+        let token_program = find_program(&programs, token_mint.to_account_info().owner)?;
+
+        // The fee token account does not have to be the associated token account
+        let fee_token = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+        if fee_token.mint != token_mint.key() || fee_token.owner != ctx.accounts.fee_config.key() {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+
+        // Create the destination token account if needed, this also verifies the address
+        CrossProgramInvocations::create_token_account(
+            ctx.accounts.signer.to_account_info(),
+            accounts[2].to_account_info(),
+            ctx.accounts.destination.to_account_info(),
+            token_mint.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            token_program.clone(),
+            ctx.accounts.associated_token_program.to_account_info(),
+        )?;
+
+        let event = withdraw_fee_token(
+            &ctx.accounts.fee_config,
+            &token_mint,
+            &fee_token,
+            &InterfaceAccount::try_from(&accounts[2])?,
+            &ctx.accounts.signer.to_account_info(),
+            token_program,
+            *amount,
+        )?;
+        emit_cpi!(event);
+    }
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFeesBatchState<'info> {
+    #[account(
+        mut,
+        constraint = fee_config.fee_withdraw_authority.key() == signer.key()
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    /// CHECK: Any wallet can receive the fees
+    #[account()]
+    pub destination: UncheckedAccount<'info>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Program<'info, Token>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,139 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface};
+
+use crate::instructions::exercise_allocation::withdraw_slot;
+use crate::state::allocation::AllocationAccount;
+use crate::state::event::AllocationWithdrawn;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::error::RuntimeError;
+
+pub fn withdraw_from_allocation(ctx: Context<WithdrawFromAllocationState>, amount: u64) -> Result<()> {
+    // A vesting allocation can only be withdrawn from once fully vested
+    if let Some(vesting) = ctx.accounts.allocation.vesting {
+        if Clock::get()?.unix_timestamp < vesting.end_timestamp {
+            return Err(RuntimeError::AllocationNotVested.into());
+        }
+    }
+
+    let token_mint = ctx.accounts.token_mint.key();
+    let index = ctx.accounts.allocation.tokens
+        .iter()
+        .position(|x| x.mint == token_mint)
+        .ok_or(RuntimeError::TokenNotFound)?;
+    let token = &mut ctx.accounts.allocation.tokens[index];
+    token.amount = token.amount
+        .checked_sub(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let programs = [
+        ctx.accounts.system_program.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.accounts.token_extensions_program.to_account_info(),
+    ];
+    let excess_amount = withdraw_slot(
+        &ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.fee_config,
+        &ctx.accounts.nft_mint,
+        &ctx.accounts.allocation,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.signer_token_account,
+        &ctx.accounts.allocation_token_account,
+        &ctx.accounts.fee_token_account,
+        &programs,
+        amount,
+    )?;
+
+    emit_cpi!(AllocationWithdrawn {
+        nft_mint: ctx.accounts.nft_mint.key(),
+        mint: token_mint,
+        amount,
+        excess: excess_amount,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFromAllocationState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [FeeConfigAccount::seed()],
+        bump,
+        constraint = fee_config.is_current()
+    )]
+    pub fee_config: Box<InterfaceAccount<'info, FeeConfigAccount>>,
+
+    #[account(
+        mut,
+        mint::authority = fee_config,
+        mint::decimals = 0,
+        mint::token_program = token_extensions_program,
+    )]
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        associated_token::mint = nft_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_extensions_program,
+        constraint = nft_token.amount == 1,
+        constraint = !nft_token.is_frozen()
+    )]
+    pub nft_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [AllocationAccount::seed(), nft_mint.key().as_ref()],
+        constraint = allocation.is_current(),
+        bump
+    )]
+    pub allocation: Box<InterfaceAccount<'info, AllocationAccount>>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = token_mint.decimals > 0
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = token_mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+        payer = signer
+    )]
+    pub signer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = allocation,
+        associated_token::token_program = token_program,
+    )]
+    pub allocation_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        associated_token::mint = token_mint,
+        associated_token::authority = fee_config,
+        associated_token::token_program = token_program,
+        payer = signer
+    )]
+    pub fee_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account()]
+    pub system_program: Program<'info, System>,
+
+    #[account()]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account()]
+    pub token_extensions_program: Program<'info, Token2022>,
+
+    #[account()]
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
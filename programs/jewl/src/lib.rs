@@ -8,36 +8,142 @@ pub mod utility;
 
 use crate::instructions::initialize_fee::{InitializeFeeState, initialize_fee as _initialize_fee, __client_accounts_initialize_fee_state};
 use crate::instructions::initialize_allocation::{InitializeAllocationState, initialize_allocation as _initialize_allocation, __client_accounts_initialize_allocation_state};
+use crate::instructions::create_allocation::{CreateAllocationState, create_allocation as _create_allocation, __client_accounts_create_allocation_state};
 use crate::instructions::increase_allocation::{IncreaseAllocationState, increase_allocation as _increase_allocation, __client_accounts_increase_allocation_state};
+use crate::instructions::increase_allocation_batch::{IncreaseAllocationBatchState, increase_allocation_batch as _increase_allocation_batch, __client_accounts_increase_allocation_batch_state};
 use crate::instructions::decrease_allocation::{DecreaseAllocationState, decrease_allocation as _decrease_allocation, __client_accounts_decrease_allocation_state};
 use crate::instructions::exercise_allocation::{ExerciseAllocationState, exercise_allocation as _exercise_allocation, __client_accounts_exercise_allocation_state};
+use crate::instructions::exercise_allocation_batch::{ExerciseAllocationBatchState, exercise_allocation_batch as _exercise_allocation_batch, __client_accounts_exercise_allocation_batch_state};
+use crate::instructions::withdraw_from_allocation::{WithdrawFromAllocationState, withdraw_from_allocation as _withdraw_from_allocation, __client_accounts_withdraw_from_allocation_state};
+use crate::instructions::split_allocation::{SplitAllocationState, split_allocation as _split_allocation, __client_accounts_split_allocation_state};
+use crate::instructions::merge_allocations::{MergeAllocationsState, merge_allocations as _merge_allocations, __client_accounts_merge_allocations_state};
+use crate::instructions::claim_vested::{ClaimVestedState, claim_vested as _claim_vested, __client_accounts_claim_vested_state};
+use crate::instructions::reclaim_expired_allocation::{ReclaimExpiredAllocationState, reclaim_expired_allocation as _reclaim_expired_allocation, __client_accounts_reclaim_expired_allocation_state};
+use crate::instructions::update_allocation_access::{UpdateAllocationAccessState, update_allocation_access as _update_allocation_access, __client_accounts_update_allocation_access_state};
+use crate::instructions::remove_slot::{RemoveSlotState, remove_slot as _remove_slot, __client_accounts_remove_slot_state};
+use crate::instructions::freeze_allocation::{FreezeAllocationState, freeze_allocation as _freeze_allocation, __client_accounts_freeze_allocation_state};
+use crate::instructions::thaw_allocation::{ThawAllocationState, thaw_allocation as _thaw_allocation, __client_accounts_thaw_allocation_state};
+use crate::instructions::recover_allocation_nft::{RecoverAllocationNftState, recover_allocation_nft as _recover_allocation_nft, __client_accounts_recover_allocation_nft_state};
+use crate::instructions::escrow_allocation::{EscrowAllocationState, escrow_allocation as _escrow_allocation, __client_accounts_escrow_allocation_state};
+use crate::instructions::claim_allocation::{ClaimAllocationState, claim_allocation as _claim_allocation, __client_accounts_claim_allocation_state};
+use crate::instructions::cancel_escrow::{CancelEscrowState, cancel_escrow as _cancel_escrow, __client_accounts_cancel_escrow_state};
+use crate::instructions::migrate_allocation::{MigrateAllocationState, migrate_allocation as _migrate_allocation, __client_accounts_migrate_allocation_state};
+use crate::instructions::migrate_fee_config::{MigrateFeeConfigState, migrate_fee_config as _migrate_fee_config, __client_accounts_migrate_fee_config_state};
+use crate::instructions::add_allowed_mint::{AddAllowedMintState, add_allowed_mint as _add_allowed_mint, __client_accounts_add_allowed_mint_state};
+use crate::instructions::remove_allowed_mint::{RemoveAllowedMintState, remove_allowed_mint as _remove_allowed_mint, __client_accounts_remove_allowed_mint_state};
 use crate::instructions::withdraw_fee::{WithdrawFeeState, withdraw_fee as _withdraw_fee, __client_accounts_withdraw_fee_state};
+use crate::instructions::withdraw_fees_batch::{WithdrawFeesBatchState, withdraw_fees_batch as _withdraw_fees_batch, __client_accounts_withdraw_fees_batch_state};
+use crate::state::allocation::{AllocationMetadata, VestingSchedule};
 
 #[program]
 pub mod jewl {
     use super::*;
 
-    pub fn initialize_fee(ctx: Context<InitializeFeeState>, fee_bps: Option<u16>, fee_authority: Option<Pubkey>, fee_withdraw_authority: Option<Pubkey>) -> Result<()> {
-        _initialize_fee(ctx, fee_bps, fee_authority, fee_withdraw_authority)
+    pub fn initialize_fee<'info>(ctx: Context<'_, '_, 'info, 'info, InitializeFeeState<'info>>, fee_bps: Option<u16>, fee_authority: Option<Pubkey>, fee_withdraw_authority: Option<Pubkey>, max_tokens: Option<u8>, default_mints: Option<Vec<Pubkey>>) -> Result<()> {
+        _initialize_fee(ctx, fee_bps, fee_authority, fee_withdraw_authority, max_tokens, default_mints)
     }
 
-    pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, decrease_athority: Option<Pubkey>) -> Result<()> {
-        _initialize_allocation(ctx, decrease_athority)
+    pub fn initialize_allocation(ctx: Context<InitializeAllocationState>, decrease_athority: Option<Pubkey>, vesting: Option<VestingSchedule>, expires_at: Option<i64>, metadata: Option<AllocationMetadata>) -> Result<()> {
+        _initialize_allocation(ctx, decrease_athority, vesting, expires_at, metadata)
     }
 
-    pub fn increase_allocation(ctx: Context<IncreaseAllocationState>, amount: u64) -> Result<()> {
-        _increase_allocation(ctx, amount)
+    pub fn create_allocation(ctx: Context<CreateAllocationState>, nonce: u64, decrease_athority: Option<Pubkey>, vesting: Option<VestingSchedule>, expires_at: Option<i64>, metadata: AllocationMetadata, soulbound: bool) -> Result<()> {
+        _create_allocation(ctx, nonce, decrease_athority, vesting, expires_at, metadata, soulbound)
     }
 
-    pub fn decrease_allocation(ctx: Context<DecreaseAllocationState>, amount: u64) -> Result<()> {
-        _decrease_allocation(ctx, amount)
+    pub fn increase_allocation(ctx: Context<IncreaseAllocationState>, amount: u64, wrap: bool) -> Result<()> {
+        _increase_allocation(ctx, amount, wrap)
     }
 
-    pub fn exercise_allocation(ctx: Context<ExerciseAllocationState>) -> Result<()> {
-        _exercise_allocation(ctx)
+    pub fn increase_allocation_batch<'info>(ctx: Context<'_, '_, 'info, 'info, IncreaseAllocationBatchState<'info>>, amounts: Vec<u64>) -> Result<()> {
+        _increase_allocation_batch(ctx, amounts)
+    }
+
+    pub fn decrease_allocation(ctx: Context<DecreaseAllocationState>, amount: u64, unwrap: bool) -> Result<()> {
+        _decrease_allocation(ctx, amount, unwrap)
+    }
+
+    pub fn exercise_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, ExerciseAllocationState<'info>>, unwrap: bool) -> Result<()> {
+        _exercise_allocation(ctx, unwrap)
+    }
+
+    pub fn exercise_allocation_batch<'info>(ctx: Context<'_, '_, 'info, 'info, ExerciseAllocationBatchState<'info>>, unwrap: bool) -> Result<()> {
+        _exercise_allocation_batch(ctx, unwrap)
+    }
+
+    pub fn withdraw_from_allocation(ctx: Context<WithdrawFromAllocationState>, amount: u64) -> Result<()> {
+        _withdraw_from_allocation(ctx, amount)
+    }
+
+    pub fn split_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, SplitAllocationState<'info>>, nonce: u64, amounts: Vec<u64>, metadata: AllocationMetadata) -> Result<()> {
+        _split_allocation(ctx, nonce, amounts, metadata)
+    }
+
+    pub fn merge_allocations<'info>(ctx: Context<'_, '_, 'info, 'info, MergeAllocationsState<'info>>) -> Result<()> {
+        _merge_allocations(ctx)
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVestedState>) -> Result<()> {
+        _claim_vested(ctx)
+    }
+
+    pub fn reclaim_expired_allocation<'info>(ctx: Context<'_, '_, 'info, 'info, ReclaimExpiredAllocationState<'info>>) -> Result<()> {
+        _reclaim_expired_allocation(ctx)
+    }
+
+    pub fn update_allocation_access(ctx: Context<UpdateAllocationAccessState>, locked: bool, increase_authority: Option<Pubkey>) -> Result<()> {
+        _update_allocation_access(ctx, locked, increase_authority)
+    }
+
+    pub fn remove_slot(ctx: Context<RemoveSlotState>, mint: Pubkey) -> Result<()> {
+        _remove_slot(ctx, mint)
+    }
+
+    pub fn freeze_allocation(ctx: Context<FreezeAllocationState>) -> Result<()> {
+        _freeze_allocation(ctx)
+    }
+
+    pub fn thaw_allocation(ctx: Context<ThawAllocationState>) -> Result<()> {
+        _thaw_allocation(ctx)
+    }
+
+    pub fn recover_allocation_nft(ctx: Context<RecoverAllocationNftState>) -> Result<()> {
+        _recover_allocation_nft(ctx)
+    }
+
+    pub fn escrow_allocation(ctx: Context<EscrowAllocationState>, claim_hash: [u8; 32]) -> Result<()> {
+        _escrow_allocation(ctx, claim_hash)
+    }
+
+    pub fn claim_allocation(ctx: Context<ClaimAllocationState>) -> Result<()> {
+        _claim_allocation(ctx)
+    }
+
+    pub fn cancel_escrow(ctx: Context<CancelEscrowState>) -> Result<()> {
+        _cancel_escrow(ctx)
+    }
+
+    pub fn migrate_allocation(ctx: Context<MigrateAllocationState>) -> Result<()> {
+        _migrate_allocation(ctx)
+    }
+
+    pub fn migrate_fee_config(ctx: Context<MigrateFeeConfigState>) -> Result<()> {
+        _migrate_fee_config(ctx)
+    }
+
+    pub fn add_allowed_mint(ctx: Context<AddAllowedMintState>) -> Result<()> {
+        _add_allowed_mint(ctx)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMintState>) -> Result<()> {
+        _remove_allowed_mint(ctx)
     }
 
     pub fn withdraw_fee(ctx: Context<WithdrawFeeState>, amount: Option<u64>) -> Result<()> {
         _withdraw_fee(ctx, amount)
     }
+
+    pub fn withdraw_fees_batch<'info>(ctx: Context<'_, '_, 'info, 'info, WithdrawFeesBatchState<'info>>, amounts: Vec<Option<u64>>) -> Result<()> {
+        _withdraw_fees_batch(ctx, amounts)
+    }
 }
@@ -1,12 +1,134 @@
-use anchor_lang::prelude::{Pubkey, account, borsh};
+use anchor_lang::prelude::{Pubkey, AccountInfo, ProgramError, Result, account, borsh};
 use anchor_lang::{Owners, AnchorSerialize, AnchorDeserialize};
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
 
 use crate::ID;
 static IDS: [Pubkey; 1] = [ID];
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct AllocationToken {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub claimed: u64,
+    pub token_program: Pubkey,
+}
+
+impl AllocationToken {
+    pub fn space() -> usize { 32 + 8 + 8 + 32 }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum VestingUnlock {
+    Linear,
+    Step { interval: i64 },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub start_timestamp: i64,
+    pub cliff_timestamp: i64,
+    pub end_timestamp: i64,
+    pub unlock: VestingUnlock,
+}
+
+impl VestingSchedule {
+    pub fn space() -> usize { 8 + 8 + 8 + 1 + 8 }
+
+    pub fn is_valid(&self) -> bool {
+        let valid_unlock = match self.unlock {
+            VestingUnlock::Linear => true,
+            VestingUnlock::Step { interval } => interval > 0,
+        };
+        valid_unlock
+            && self.start_timestamp <= self.cliff_timestamp
+            && self.cliff_timestamp <= self.end_timestamp
+            && self.start_timestamp < self.end_timestamp
+    }
+
+    // The part of `total` that is unlocked at `timestamp`
+    pub fn unlocked_amount(&self, total: u64, timestamp: i64) -> Result<u64> {
+        if timestamp < self.cliff_timestamp {
+            return Ok(0);
+        }
+        if timestamp >= self.end_timestamp {
+            return Ok(total);
+        }
+        let elapsed = timestamp.saturating_sub(self.start_timestamp);
+        let elapsed = match self.unlock {
+            VestingUnlock::Linear => elapsed,
+            VestingUnlock::Step { interval } => elapsed - elapsed % interval,
+        };
+        let duration = self.end_timestamp.saturating_sub(self.start_timestamp);
+        let unlocked = u128::from(total)
+            .checked_mul(elapsed as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .checked_div(duration as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(u64::try_from(unlocked).map_err(|_| ProgramError::ArithmeticOverflow)?)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct AllocationMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
 #[account]
 #[derive(Default, Debug, PartialEq)]
 pub struct AllocationAccount {
+    pub version: u8,
+    pub initialized: bool,
+    pub decrease_authority: Pubkey,
+    pub recover_authority: Pubkey,
+    pub vesting: Option<VestingSchedule>,
+    pub expires_at: Option<i64>,
+    pub increase_authority: Option<Pubkey>,
+    pub locked: bool,
+    pub reserved: [u8; 32],
+    pub tokens: Vec<AllocationToken>,
+}
+
+impl AllocationAccount {
+    // Accounts from before versioning start with the initialized flag so they read as the legacy version
+    pub const LEGACY_VERSION: u8 = 1;
+    pub const VERSION: u8 = 2;
+
+    pub fn space(tokens: usize) -> usize { 8 + 1 + 1 + 32 + 32 + 1 + VestingSchedule::space() + 1 + 8 + 1 + 32 + 1 + 32 + 4 + tokens * AllocationToken::space() }
+    pub fn seed() -> &'static [u8] { b"allocation" }
+    pub fn mint_seed() -> &'static [u8] { b"allocation_mint" }
+    pub fn unwrap_seed() -> &'static [u8] { b"allocation_unwrap" }
+
+    // Existing allocations can have grown past the initial size so keep their current size
+    pub fn space_for(info: &AccountInfo, tokens: usize) -> usize {
+        if info.data_is_empty() { Self::space(tokens) } else { info.data_len() }
+    }
+
+    // Whether the allocation is initialized and stored in the current layout
+    pub fn is_current(&self) -> bool {
+        self.initialized && self.version == Self::VERSION
+    }
+
+    // Whether the signer may add tokens to this allocation
+    pub fn can_increase(&self, signer: &Pubkey) -> bool {
+        match self.increase_authority {
+            Some(authority) => authority == *signer,
+            None => true,
+        }
+    }
+
+    // The size after adding a token, reusing its slot or an empty slot if possible
+    pub fn space_with(&self, mint: &Pubkey) -> usize {
+        let has_slot = self.tokens.iter().any(|x| x.mint == *mint || x.amount == 0);
+        let tokens = if has_slot { self.tokens.len() } else { self.tokens.len() + 1 };
+        Self::space(tokens)
+    }
+}
+
+// The allocation layout deployed before versioning with three fixed token slots
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq)]
+pub struct LegacyAllocationAccount {
     pub initialized: bool,
     pub decrease_authority: Pubkey,
     pub recover_authority: Pubkey,
@@ -18,9 +140,27 @@ pub struct AllocationAccount {
     pub third_token_amount: u64,
 }
 
-impl AllocationAccount {
-    pub fn space() -> usize { 8 + 1 + 32 + 32 + 32 + 8 + 32 + 8 + 32 + 8 }
-    pub fn seed() -> &'static [u8] { b"allocation" }
+impl From<LegacyAllocationAccount> for AllocationAccount {
+    fn from(legacy: LegacyAllocationAccount) -> Self {
+        // Legacy allocations only supported mints of the token program
+        let token = |mint: Pubkey, amount: u64| AllocationToken { mint, amount, claimed: 0, token_program: TOKEN_PROGRAM_ID };
+        Self {
+            version: Self::VERSION,
+            initialized: legacy.initialized,
+            decrease_authority: legacy.decrease_authority,
+            recover_authority: legacy.recover_authority,
+            vesting: None,
+            expires_at: None,
+            increase_authority: None,
+            locked: false,
+            reserved: [0; 32],
+            tokens: vec![
+                token(legacy.first_token_mint, legacy.first_token_amount),
+                token(legacy.second_token_mint, legacy.second_token_amount),
+                token(legacy.third_token_mint, legacy.third_token_amount),
+            ],
+        }
+    }
 }
 
 impl Owners for AllocationAccount {
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::{Pubkey, account, borsh};
+use anchor_lang::{Owners, AnchorSerialize, AnchorDeserialize};
+
+use crate::ID;
+static IDS: [Pubkey; 1] = [ID];
+
+#[account]
+#[derive(Default, Debug, PartialEq)]
+pub struct AllowedMintAccount {
+    pub initialized: bool,
+    pub mint: Pubkey,
+}
+
+impl AllowedMintAccount {
+    pub fn space() -> usize { 8 + 1 + 32 }
+    pub fn seed() -> &'static [u8] { b"allowed_mint" }
+}
+
+impl Owners for AllowedMintAccount {
+    fn owners() -> &'static [Pubkey] {
+        &IDS
+    }
+}
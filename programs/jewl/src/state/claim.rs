@@ -0,0 +1,24 @@
+use anchor_lang::prelude::{Pubkey, account, borsh};
+use anchor_lang::{Owners, AnchorSerialize, AnchorDeserialize};
+
+use crate::ID;
+static IDS: [Pubkey; 1] = [ID];
+
+#[account]
+#[derive(Default, Debug, PartialEq)]
+pub struct ClaimAccount {
+    pub initialized: bool,
+    pub creator: Pubkey,
+    pub claim_hash: [u8; 32],
+}
+
+impl ClaimAccount {
+    pub fn space() -> usize { 8 + 1 + 32 + 32 }
+    pub fn seed() -> &'static [u8] { b"claim" }
+}
+
+impl Owners for ClaimAccount {
+    fn owners() -> &'static [Pubkey] {
+        &IDS
+    }
+}
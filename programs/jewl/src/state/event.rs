@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub fee_authority: Pubkey,
+    pub fee_withdraw_authority: Pubkey,
+    pub fee_bps: u16,
+    pub max_tokens: u8,
+    pub default_mints: Vec<Pubkey>,
+}
+
+#[event]
+pub struct AllocationInitialized {
+    pub nft_mint: Pubkey,
+    pub decrease_authority: Pubkey,
+    pub recover_authority: Pubkey,
+}
+
+#[event]
+pub struct AllocationIncreased {
+    pub nft_mint: Pubkey,
+    pub mint: Pubkey,
+    pub gross: u64,
+    pub fee: u64,
+    pub net: u64,
+    pub excess: u64,
+}
+
+#[event]
+pub struct AllocationDecreased {
+    pub nft_mint: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub excess: u64,
+}
+
+#[event]
+pub struct AllocationWithdrawn {
+    pub nft_mint: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub excess: u64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub nft_mint: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub excess: u64,
+}
+
+#[event]
+pub struct AllocationSplit {
+    pub nft_mint: Pubkey,
+    pub new_nft_mint: Pubkey,
+    pub amounts: Vec<u64>,
+}
+
+#[event]
+pub struct AllocationsMerged {
+    pub nft_mint: Pubkey,
+    pub source_nft_mint: Pubkey,
+}
+
+#[event]
+pub struct AllocationFrozen {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct AllocationThawed {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct SlotRemoved {
+    pub nft_mint: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AllocationAccessUpdated {
+    pub nft_mint: Pubkey,
+    pub locked: bool,
+    pub increase_authority: Option<Pubkey>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ExercisedToken {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub excess: u64,
+}
+
+#[event]
+pub struct AllocationExercised {
+    pub nft_mint: Pubkey,
+    pub owner: Pubkey,
+    pub tokens: Vec<ExercisedToken>,
+}
+
+#[event]
+pub struct AllocationReclaimed {
+    pub nft_mint: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub tokens: Vec<ExercisedToken>,
+}
+
+#[event]
+pub struct FeeWithdrawn {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct AllocationEscrowed {
+    pub nft_mint: Pubkey,
+    pub creator: Pubkey,
+    pub claim_hash: [u8; 32],
+}
+
+#[event]
+pub struct AllocationClaimed {
+    pub nft_mint: Pubkey,
+    pub claimant: Pubkey,
+}
+
+#[event]
+pub struct EscrowCancelled {
+    pub nft_mint: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct AllocationNftRecovered {
+    pub nft_mint: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct AllowedMintAdded {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AllowedMintRemoved {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct AllocationMigrated {
+    pub nft_mint: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct FeeConfigMigrated {
+    pub from_version: u8,
+    pub to_version: u8,
+    pub fee_authority: Pubkey,
+    pub fee_withdraw_authority: Pubkey,
+    pub fee_bps: u16,
+}
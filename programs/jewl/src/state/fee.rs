@@ -1,21 +1,101 @@
-use anchor_lang::prelude::{Pubkey, account, borsh};
+use anchor_lang::prelude::{Pubkey, AccountInfo, account, borsh};
 use anchor_lang::{Owners, AnchorSerialize, AnchorDeserialize};
+use anchor_spl::token::ID as TOKEN_PROGRAM_ID;
 
 use crate::ID;
 static IDS: [Pubkey; 1] = [ID];
 
+static SOL_MINT: Pubkey = Pubkey::new_from_array([
+    6, 155, 136, 87, 254, 171, 129, 132,
+    251, 104, 127, 99, 70, 24, 192, 53,
+    218, 196, 57, 220, 26, 235, 59, 85,
+    152, 160, 240, 0, 0, 0, 0, 1
+]);
+
+static USDC_MINT: Pubkey = Pubkey::new_from_array([
+    198, 250, 122, 243, 190, 219, 173, 58,
+    61, 101, 243, 106, 171, 201, 116, 49,
+    177, 187, 228, 194, 210, 246, 224, 228,
+    124, 166, 2, 3, 69, 47, 93, 97
+]);
+
+static USDT_MINT: Pubkey = Pubkey::new_from_array([
+    206, 1, 14, 96, 175, 237, 178, 39,
+    23, 189, 99, 25, 47, 84, 20, 90,
+    63, 150, 90, 51, 187, 130, 210, 199,
+    2, 158, 178, 206, 30, 32, 130, 100
+]);
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct DefaultMint {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl DefaultMint {
+    pub fn space() -> usize { 32 + 32 }
+}
+
 #[account]
 #[derive(Default, Debug, PartialEq)]
 pub struct FeeConfigAccount {
+    pub version: u8,
     pub initialized: bool,
     pub fee_authority: Pubkey,
     pub fee_withdraw_authority: Pubkey,
     pub fee_bps: u16,
+    pub max_tokens: u8,
+    pub reserved: [u8; 32],
+    pub default_mints: Vec<DefaultMint>,
 }
 
 impl FeeConfigAccount {
-    pub fn space() -> usize { 8 + 1 + 32 + 32 + 2 }
+    // Accounts from before versioning start with the initialized flag so they read as the legacy version
+    pub const LEGACY_VERSION: u8 = 1;
+    pub const VERSION: u8 = 2;
+    // Exercising needs four accounts per slot so larger baskets need an address lookup table to fit in a transaction
+    pub const MAX_TOKENS: u8 = 10;
+    pub const DEFAULT_MAX_TOKENS: u8 = 5;
+
+    pub fn space(default_mints: usize) -> usize { 8 + 1 + 1 + 32 + 32 + 2 + 1 + 32 + 4 + default_mints * DefaultMint::space() }
     pub fn seed() -> &'static [u8] { b"fee" }
+
+    // Whether the fee config is initialized and stored in the current layout
+    pub fn is_current(&self) -> bool {
+        self.initialized && self.version == Self::VERSION
+    }
+
+    // Existing fee configs can have grown past the initial size so keep their current size
+    pub fn space_for(info: &AccountInfo) -> usize {
+        if info.data_is_empty() { Self::space(0) } else { info.data_len() }
+    }
+}
+
+// The fee config layout deployed before versioning, default mints were fixed to sol, usdc and usdt
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug, PartialEq)]
+pub struct LegacyFeeConfigAccount {
+    pub initialized: bool,
+    pub fee_authority: Pubkey,
+    pub fee_withdraw_authority: Pubkey,
+    pub fee_bps: u16,
+}
+
+impl From<LegacyFeeConfigAccount> for FeeConfigAccount {
+    fn from(legacy: LegacyFeeConfigAccount) -> Self {
+        Self {
+            version: Self::VERSION,
+            initialized: legacy.initialized,
+            fee_authority: legacy.fee_authority,
+            fee_withdraw_authority: legacy.fee_withdraw_authority,
+            fee_bps: legacy.fee_bps,
+            max_tokens: Self::DEFAULT_MAX_TOKENS,
+            reserved: [0; 32],
+            default_mints: [SOL_MINT, USDC_MINT, USDT_MINT]
+                .iter()
+                .map(|mint| DefaultMint { mint: *mint, token_program: TOKEN_PROGRAM_ID })
+                .collect(),
+        }
+    }
 }
 
 impl Owners for FeeConfigAccount {
@@ -1,2 +1,5 @@
 pub mod allocation;
+pub mod allowlist;
+pub mod claim;
+pub mod event;
 pub mod fee;
@@ -1,6 +1,12 @@
-use anchor_lang::prelude::{CpiContext, AccountInfo, Result};
+use anchor_lang::prelude::{CpiContext, AccountInfo, Pubkey, Rent, Result, SolanaSysvar};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer};
 use anchor_lang::{ToAccountInfos, ToAccountMetas};
-use anchor_spl::token_2022::{burn, close_account, freeze_account, mint_to, thaw_account, transfer_checked, Burn, CloseAccount, FreezeAccount, MintTo, ThawAccount, TransferChecked};
+use anchor_spl::associated_token::{create_idempotent, Create};
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::instruction::remove_key;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::Field;
+use anchor_spl::token_2022_extensions::{metadata_pointer_initialize, mint_close_authority_initialize, non_transferable_mint_initialize, permanent_delegate_initialize, token_metadata_initialize, token_metadata_update_field, MetadataPointerInitialize, MintCloseAuthorityInitialize, NonTransferableMintInitialize, PermanentDelegateInitialize, TokenMetadataInitialize, TokenMetadataUpdateField};
+use anchor_spl::token_2022::{burn, close_account, freeze_account, initialize_mint2, mint_to, sync_native, thaw_account, transfer_checked, Burn, CloseAccount, FreezeAccount, InitializeMint2, MintTo, SyncNative, ThawAccount, TransferChecked};
 
 use crate::utility::signer::SeedSigner;
 
@@ -39,6 +45,27 @@ impl <'a, 'b, 'c, 'info> CrossProgramInvocations {
         transfer_checked(context, amount, decimals)
     }
 
+    pub fn create_token_account(
+      payer: AccountInfo<'info>,
+      account: AccountInfo<'info>,
+      authority: AccountInfo<'info>,
+      mint: AccountInfo<'info>,
+      system_program: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      associated_token_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        let accounts = Create {
+            payer,
+            associated_token: account,
+            authority,
+            mint,
+            system_program,
+            token_program,
+        };
+        let context = Self::cpi_context(associated_token_program, accounts, None);
+        create_idempotent(context)
+    }
+
     pub fn close_token(
       account: AccountInfo<'info>,
       destination: AccountInfo<'info>,
@@ -120,4 +147,227 @@ impl <'a, 'b, 'c, 'info> CrossProgramInvocations {
         let context = Self::cpi_context(token_program, accounts, signer);
         burn(context, amount)
     }
+
+    pub fn sync_token(
+      account: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        let accounts = SyncNative {
+            account,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        sync_native(context)
+    }
+
+    pub fn transfer_sol(
+      sender: AccountInfo<'info>,
+      receiver: AccountInfo<'info>,
+      system_program: AccountInfo<'info>,
+      signer: Option<SeedSigner<'a, 'b, 'c>>,
+      amount: u64,
+    ) -> Result<()> {
+        let accounts = Transfer {
+            from: sender,
+            to: receiver,
+        };
+        let context = Self::cpi_context(system_program, accounts, signer);
+        transfer(context, amount)
+    }
+
+    pub fn create_account(
+      payer: AccountInfo<'info>,
+      account: AccountInfo<'info>,
+      system_program: AccountInfo<'info>,
+      signer: Option<SeedSigner<'a, 'b, 'c>>,
+      space: usize,
+      owner: &Pubkey,
+    ) -> Result<()> {
+        let rent = Rent::get()?.minimum_balance(space);
+        let lamports = account.lamports();
+        if lamports == 0 {
+            let accounts = CreateAccount {
+                from: payer,
+                to: account,
+            };
+            let context = Self::cpi_context(system_program, accounts, signer);
+            return create_account(context, rent, space as u64, owner);
+        }
+
+        // Anyone can send lamports to the address up front so top it up and allocate and assign it instead
+        if rent > lamports {
+            Self::transfer_sol(
+                payer,
+                account.clone(),
+                system_program.clone(),
+                None,
+                rent - lamports,
+            )?;
+        }
+        let accounts = Allocate {
+            account_to_allocate: account.clone(),
+        };
+        let context = Self::cpi_context(system_program.clone(), accounts, signer);
+        allocate(context, space as u64)?;
+        let accounts = Assign {
+            account_to_assign: account,
+        };
+        let context = Self::cpi_context(system_program, accounts, signer);
+        assign(context, owner)
+    }
+
+    pub fn initialize_mint(
+      mint: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      authority: &Pubkey,
+      freeze_authority: Option<&Pubkey>,
+      decimals: u8,
+    ) -> Result<()> {
+        let accounts = InitializeMint2 {
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        initialize_mint2(context, decimals, authority, freeze_authority)
+    }
+
+    pub fn initialize_mint_close_authority(
+      mint: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      authority: &Pubkey,
+    ) -> Result<()> {
+        let accounts = MintCloseAuthorityInitialize {
+            token_program_id: token_program.clone(),
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        mint_close_authority_initialize(context, Some(authority))
+    }
+
+    pub fn initialize_non_transferable(
+      mint: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        let accounts = NonTransferableMintInitialize {
+            token_program_id: token_program.clone(),
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        non_transferable_mint_initialize(context)
+    }
+
+    pub fn initialize_permanent_delegate(
+      mint: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      delegate: &Pubkey,
+    ) -> Result<()> {
+        let accounts = PermanentDelegateInitialize {
+            token_program_id: token_program.clone(),
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        permanent_delegate_initialize(context, delegate)
+    }
+
+    pub fn initialize_metadata_pointer(
+      mint: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      metadata: Pubkey,
+    ) -> Result<()> {
+        let accounts = MetadataPointerInitialize {
+            token_program_id: token_program.clone(),
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, None);
+        metadata_pointer_initialize(context, None, Some(metadata))
+    }
+
+    pub fn initialize_metadata(
+      mint: AccountInfo<'info>,
+      authority: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      signer: Option<SeedSigner<'a, 'b, 'c>>,
+      name: String,
+      symbol: String,
+      uri: String,
+    ) -> Result<()> {
+        let accounts = TokenMetadataInitialize {
+            token_program_id: token_program.clone(),
+            metadata: mint.clone(),
+            update_authority: authority.clone(),
+            mint_authority: authority,
+            mint,
+        };
+        let context = Self::cpi_context(token_program, accounts, signer);
+        token_metadata_initialize(context, name, symbol, uri)
+    }
+
+    pub fn update_metadata_field(
+      mint: AccountInfo<'info>,
+      update_authority: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      signer: Option<SeedSigner<'a, 'b, 'c>>,
+      field: String,
+      value: String,
+    ) -> Result<()> {
+        let accounts = TokenMetadataUpdateField {
+            token_program_id: token_program.clone(),
+            metadata: mint,
+            update_authority,
+        };
+        let context = Self::cpi_context(token_program, accounts, signer);
+        token_metadata_update_field(context, Field::Key(field), value)
+    }
+
+    pub fn remove_metadata_field(
+      mint: AccountInfo<'info>,
+      update_authority: AccountInfo<'info>,
+      token_program: AccountInfo<'info>,
+      signer: Option<SeedSigner<'a, 'b, 'c>>,
+      field: String,
+    ) -> Result<()> {
+        let instruction = remove_key(token_program.key, mint.key, update_authority.key, field, true);
+        let accounts = [mint, update_authority, token_program];
+        invoke_signed(&instruction, &accounts, signer.unwrap_or_default())?;
+        Ok(())
+    }
+
+    pub fn fund_account(
+      account: AccountInfo<'info>,
+      payer: AccountInfo<'info>,
+      system_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        let rent = Rent::get()?.minimum_balance(account.data_len());
+        if rent > account.lamports() {
+            Self::transfer_sol(
+                payer,
+                account.clone(),
+                system_program,
+                None,
+                rent - account.lamports(),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn grow_account(
+      account: AccountInfo<'info>,
+      payer: AccountInfo<'info>,
+      system_program: AccountInfo<'info>,
+      space: usize,
+    ) -> Result<()> {
+        if account.data_len() >= space {
+            return Ok(());
+        }
+        let rent = Rent::get()?.minimum_balance(space);
+        if rent > account.lamports() {
+            Self::transfer_sol(
+                payer,
+                account.clone(),
+                system_program,
+                None,
+                rent - account.lamports(),
+            )?;
+        }
+        account.realloc(space, false)?;
+        Ok(())
+    }
 }
@@ -5,6 +5,51 @@ pub enum RuntimeError {
     #[msg("Token does not exist in allocation")]
     TokenNotFound,
 
-    #[msg("Allocation already has three tokens")]
+    #[msg("Allocation already has the maximum number of tokens")]
     MaxTokensReached,
+
+    #[msg("Vesting schedule is invalid")]
+    InvalidVestingSchedule,
+
+    #[msg("Allocation is not fully vested")]
+    AllocationNotVested,
+
+    #[msg("Allocation does not have a vesting schedule")]
+    NoVestingSchedule,
+
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+
+    #[msg("Allocation has not expired")]
+    AllocationNotExpired,
+
+    #[msg("Token is not the native mint")]
+    NotNativeMint,
+
+    #[msg("Mint is not on the allowlist")]
+    MintNotAllowed,
+
+    #[msg("Signer is not the increase authority")]
+    NotIncreaseAuthority,
+
+    #[msg("Allocation tokens are locked")]
+    AllocationLocked,
+
+    #[msg("Token slot is not empty")]
+    SlotNotEmpty,
+
+    #[msg("Nft metadata is invalid")]
+    InvalidMetadata,
+
+    #[msg("Allocations have a different vesting schedule or expiry")]
+    AllocationMismatch,
+
+    #[msg("Claim signature is invalid")]
+    InvalidClaimSignature,
+
+    #[msg("A soulbound allocation cannot be merged into a transferable allocation")]
+    SoulboundMismatch,
+
+    #[msg("Mint has a permanent delegate, transfer hook or frozen default account state")]
+    UnsafeMintExtension,
 }
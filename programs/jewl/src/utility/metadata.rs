@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as StateMint;
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata;
+
+use crate::id;
+use crate::state::allocation::AllocationToken;
+use crate::state::fee::FeeConfigAccount;
+use crate::utility::cpi::CrossProgramInvocations;
+use crate::utility::signer::SeedSigner;
+
+// The slot fields currently on the nft, or none if the metadata is not owned by the fee config
+fn slot_fields(nft_mint: &AccountInfo, fee_config: &Pubkey) -> Result<Option<Vec<String>>> {
+    let mint_data = nft_mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<StateMint>::unpack(&mint_data)?;
+    let Ok(metadata) = mint_state.get_variable_len_extension::<TokenMetadata>() else {
+        return Ok(None);
+    };
+    let update_authority: Option<Pubkey> = metadata.update_authority.into();
+    if update_authority != Some(*fee_config) {
+        return Ok(None);
+    }
+    let fields = metadata.additional_metadata
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| key.starts_with("slot"))
+        .collect();
+    Ok(Some(fields))
+}
+
+// Write a slot of the allocation to the nft metadata so wallets can show the contents
+pub fn sync_slot_metadata<'info>(
+    nft_mint: AccountInfo<'info>,
+    fee_config: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_extensions_program: AccountInfo<'info>,
+    token: &AllocationToken,
+    index: usize,
+) -> Result<()> {
+    // Nfts without metadata owned by the fee config cannot be updated
+    if slot_fields(&nft_mint, &fee_config.key())?.is_none() {
+        return Ok(());
+    }
+
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+    CrossProgramInvocations::update_metadata_field(
+        nft_mint.clone(),
+        fee_config,
+        token_extensions_program,
+        Some(signer),
+        format!("slot{}", index),
+        format!("{}:{}", token.mint, token.amount),
+    )?;
+
+    // The metadata can grow so keep the mint rent exempt
+    CrossProgramInvocations::fund_account(nft_mint, payer, system_program)
+}
+
+// Rewrite every slot after slots moved and remove the fields of slots that no longer exist
+pub fn sync_all_slot_metadata<'info>(
+    nft_mint: AccountInfo<'info>,
+    fee_config: AccountInfo<'info>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_extensions_program: AccountInfo<'info>,
+    tokens: &[AllocationToken],
+) -> Result<()> {
+    let Some(fields) = slot_fields(&nft_mint, &fee_config.key())? else {
+        return Ok(());
+    };
+
+    let bump = Pubkey::find_program_address(&[FeeConfigAccount::seed()], &id()).1;
+    let signer: SeedSigner = &[&[FeeConfigAccount::seed(), &[bump]]];
+    for (index, token) in tokens.iter().enumerate() {
+        CrossProgramInvocations::update_metadata_field(
+            nft_mint.clone(),
+            fee_config.clone(),
+            token_extensions_program.clone(),
+            Some(signer),
+            format!("slot{}", index),
+            format!("{}:{}", token.mint, token.amount),
+        )?;
+    }
+
+    let current: Vec<String> = (0..tokens.len()).map(|index| format!("slot{}", index)).collect();
+    for field in fields.into_iter().filter(|x| !current.contains(x)) {
+        CrossProgramInvocations::remove_metadata_field(
+            nft_mint.clone(),
+            fee_config.clone(),
+            token_extensions_program.clone(),
+            Some(signer),
+            field,
+        )?;
+    }
+
+    // The metadata can grow so keep the mint rent exempt
+    CrossProgramInvocations::fund_account(nft_mint, payer, system_program)
+}
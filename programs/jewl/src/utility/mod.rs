@@ -1,4 +1,7 @@
 pub mod cpi;
 pub mod error;
+pub mod metadata;
 pub mod security;
 pub mod signer;
+pub mod sigverify;
+pub mod token;
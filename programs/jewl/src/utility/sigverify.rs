@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+
+use crate::utility::error::RuntimeError;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+
+// Find the public key that signed the message in the ed25519 instruction right before the current one
+pub fn ed25519_signer(instructions: &AccountInfo, message: &[u8]) -> Result<Pubkey> {
+    let index = load_current_index_checked(instructions)?;
+    let previous = index.checked_sub(1).ok_or(RuntimeError::InvalidClaimSignature)?;
+    let instruction = load_instruction_at_checked(previous.into(), instructions)?;
+    if instruction.program_id != ed25519_program::ID {
+        return Err(RuntimeError::InvalidClaimSignature.into());
+    }
+
+    // Only a single signature with all data inside the ed25519 instruction itself is accepted
+    let data = instruction.data;
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE || data[0] != 1 {
+        return Err(RuntimeError::InvalidClaimSignature.into());
+    }
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE];
+    let read = |i: usize| usize::from(u16::from_le_bytes([offsets[i], offsets[i + 1]]));
+    let (signature_index, pubkey_offset, pubkey_index) = (read(2), read(4), read(6));
+    let (message_offset, message_size, message_index) = (read(8), read(10), read(12));
+    let current = usize::from(u16::MAX);
+    if signature_index != current || pubkey_index != current || message_index != current {
+        return Err(RuntimeError::InvalidClaimSignature.into());
+    }
+
+    let pubkey = data
+        .get(pubkey_offset..pubkey_offset + PUBKEY_SIZE)
+        .ok_or(RuntimeError::InvalidClaimSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(RuntimeError::InvalidClaimSignature)?;
+    if signed_message != message {
+        return Err(RuntimeError::InvalidClaimSignature.into());
+    }
+
+    Pubkey::try_from(pubkey).map_err(|_| RuntimeError::InvalidClaimSignature.into())
+}
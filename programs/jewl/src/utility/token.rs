@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::default_account_state::DefaultAccountState;
+use anchor_spl::token_2022::spl_token_2022::extension::non_transferable::NonTransferable;
+use anchor_spl::token_2022::spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_hook::TransferHook;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::{Account as StateAccount, AccountState, Mint as StateMint};
+use anchor_spl::token_2022::ID as TOKEN_2022_ID;
+
+// Token accounts of transfer fee mints cannot be closed while they hold withheld fees
+pub fn has_withheld_fees(account: &AccountInfo) -> Result<bool> {
+    if *account.owner != TOKEN_2022_ID {
+        return Ok(false);
+    }
+    let data = account.try_borrow_data()?;
+    let state = StateWithExtensions::<StateAccount>::unpack(&data)?;
+    let withheld = state
+        .get_extension::<TransferFeeAmount>()
+        .map(|x| u64::from(x.withheld_amount))
+        .unwrap_or(0);
+    Ok(withheld > 0)
+}
+
+// Soulbound allocation nfts are minted with the non transferable extension
+pub fn is_soulbound(mint: &AccountInfo) -> Result<bool> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<StateMint>::unpack(&data)?;
+    Ok(state.get_extension::<NonTransferable>().is_ok())
+}
+
+// Only mints with the given permanent delegate can have their tokens burned without the holder
+pub fn is_permanent_delegate(mint: &AccountInfo, delegate: &Pubkey) -> Result<bool> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<StateMint>::unpack(&data)?;
+    let current: Option<Pubkey> = match state.get_extension::<PermanentDelegate>() {
+        Ok(extension) => extension.delegate.into(),
+        Err(_) => None,
+    };
+    Ok(current.as_ref() == Some(delegate))
+}
+
+// Tokens of mints with a permanent delegate, a transfer hook or frozen accounts by default can be moved or locked up outside the allocation
+pub fn has_unsafe_extensions(mint: &AccountInfo) -> Result<bool> {
+    if *mint.owner != TOKEN_2022_ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<StateMint>::unpack(&data)?;
+    let permanent_delegate = state
+        .get_extension::<PermanentDelegate>()
+        .is_ok_and(|x| Option::<Pubkey>::from(x.delegate).is_some());
+    let transfer_hook = state
+        .get_extension::<TransferHook>()
+        .is_ok_and(|x| Option::<Pubkey>::from(x.program_id).is_some() || Option::<Pubkey>::from(x.authority).is_some());
+    let default_frozen = state
+        .get_extension::<DefaultAccountState>()
+        .is_ok_and(|x| x.state == AccountState::Frozen as u8);
+    Ok(permanent_delegate || transfer_hook || default_frozen)
+}
+
+// Pick the program with the given id out of the passed program accounts
+pub fn find_program<'a, 'info>(programs: &'a [AccountInfo<'info>], program_id: &Pubkey) -> Result<&'a AccountInfo<'info>> {
+    programs
+        .iter()
+        .find(|x| x.key == program_id)
+        .ok_or(ProgramError::IncorrectProgramId.into())
+}
@@ -12,6 +12,7 @@ describe("coder", () => {
     string: "1000000",
     boolean: true,
     bn: new BN(1000000),
+    nested: [{ publicKey: pubkey, bn: new BN(1000000) }],
   };
 
   const bigint = {
@@ -20,6 +21,7 @@ describe("coder", () => {
     string: "1000000",
     boolean: true,
     bn: 1000000n,
+    nested: [{ publicKey: pubkey, bn: 1000000n }],
   };
 
   it("Should be able to convert object from BN to BigInt", () => {
@@ -0,0 +1,65 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setTokenMint, setFeeConfig, setAllowedMint, getAllowedMint, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createAddAllowedMintInstruction } from "@/core/instruction";
+import { allowedMintAddress } from "@/core/address";
+
+describe("add_allowed_mint", () => {
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const allowedMint = allowedMintAddress(tokenMintAddress);
+  let instruction: TransactionInstruction;
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    instruction = createAddAllowedMintInstruction({
+      payer: signerAddress,
+      tokenMint: tokenMintAddress,
+    });
+  });
+
+  it("Should be able to add a mint to the allowlist", async () => {
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allowedMintAdded");
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    const account = await getAllowedMint(allowedMint);
+    assert.strictEqual(account.initialized, true);
+    assert.strictEqual(account.mint.toBase58(), tokenMintAddress.toBase58());
+  });
+
+  it("Should not be able to add a mint that is already allowed", async () => {
+    await setAllowedMint({ mint: tokenMintAddress });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to add a mint with zero decimals", async () => {
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1n,
+      decimals: 0,
+    });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to add a mint if not the fee authority", async () => {
+    await setFeeConfig({ feeAuthority: PublicKey.default });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to add a mint with an uninitialized fee config", async () => {
+    await setFeeConfig({ initialized: false });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+});
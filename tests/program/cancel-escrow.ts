@@ -0,0 +1,86 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAccount, getEmittedEvent } from "@/tests/program";
+import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import { createCancelEscrowInstruction, createEscrowAllocationInstruction } from "@/core/instruction";
+import { associatedTokenAddress, claimAddress, feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
+import { claimHash } from "@/core/sign";
+
+describe("cancel_escrow", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftClaimAddress = claimAddress(nftMintAddress);
+  const escrowNftTokenAddress = associatedTokenAddress(nftClaimAddress, nftMintAddress, tokenExtensionsProgramId);
+  const escrowInstruction = createEscrowAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    claimHash: claimHash(Keypair.generate().publicKey),
+  });
+  const instruction = createCancelEscrowInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({ nftMint: nftMintAddress });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await testTransaction([escrowInstruction]);
+  });
+
+  it("Should be able to cancel an escrow", async () => {
+    const meta = await testTransaction([instruction]);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+    const escrowPromise = getAccount(escrowNftTokenAddress);
+    await assert.rejects(escrowPromise);
+    const claimPromise = getAccount(nftClaimAddress);
+    await assert.rejects(claimPromise);
+    const event = getEmittedEvent(meta, "escrowCancelled");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.creator.toBase58(), signerAddress.toBase58());
+  });
+
+  it("Should not be able to cancel an escrow twice", async () => {
+    await testTransaction([instruction]);
+    const promise = testTransaction([
+      SystemProgram.transfer({ fromPubkey: signerAddress, toPubkey: Keypair.generate().publicKey, lamports: LAMPORTS_PER_SOL }),
+      instruction,
+    ]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to cancel an escrow if not the creator", async () => {
+    const other = Keypair.generate();
+    const promise = testTransaction([
+      SystemProgram.transfer({ fromPubkey: signerAddress, toPubkey: other.publicKey, lamports: LAMPORTS_PER_SOL }),
+      createCancelEscrowInstruction({
+        payer: other.publicKey,
+        nftMint: nftMintAddress,
+      }),
+    ], other);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to cancel an allocation that is not escrowed", async () => {
+    const promise = testTransaction([createCancelEscrowInstruction({
+      payer: signerAddress,
+      nftMint: Keypair.generate().publicKey,
+    })]);
+    await assert.rejects(promise);
+  });
+});
@@ -0,0 +1,160 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAccount, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Ed25519Program, Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import { createClaimAllocationInstruction, createEscrowAllocationInstruction } from "@/core/instruction";
+import { associatedTokenAddress, claimAddress, feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
+import { claimHash, claimMessage } from "@/core/sign";
+
+describe("claim_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftClaimAddress = claimAddress(nftMintAddress);
+  const escrowNftTokenAddress = associatedTokenAddress(nftClaimAddress, nftMintAddress, tokenExtensionsProgramId);
+  const claimKey = Keypair.generate();
+  const claimant = Keypair.generate();
+  const claimantNftTokenAddress = associatedTokenAddress(claimant.publicKey, nftMintAddress, tokenExtensionsProgramId);
+  const escrowInstruction = createEscrowAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    claimHash: claimHash(claimKey.publicKey),
+  });
+  const claimInstructions = (props?: { key?: Keypair, message?: Buffer, lamports?: number }): Array<TransactionInstruction> => [
+    SystemProgram.transfer({ fromPubkey: signerAddress, toPubkey: claimant.publicKey, lamports: props?.lamports ?? LAMPORTS_PER_SOL }),
+    Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: (props?.key ?? claimKey).secretKey,
+      message: props?.message ?? claimMessage(nftMintAddress, claimant.publicKey),
+    }),
+    createClaimAllocationInstruction({
+      payer: claimant.publicKey,
+      nftMint: nftMintAddress,
+      creator: signerAddress,
+    }),
+  ];
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({ nftMint: nftMintAddress });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+  });
+
+  it("Should be able to escrow an allocation", async () => {
+    const meta = await testTransaction([escrowInstruction]);
+    const escrowNftToken = await getTokenAccount(escrowNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(escrowNftToken.amount, 1n);
+    const nftTokenPromise = getAccount(nftTokenAddress);
+    await assert.rejects(nftTokenPromise);
+    const claim = await getAccount(nftClaimAddress);
+    assert.strictEqual(claim.owner.toBase58(), escrowInstruction.programId.toBase58());
+    assert.deepStrictEqual(Buffer.from(claim.data.subarray(41, 73)), claimHash(claimKey.publicKey));
+    const event = getEmittedEvent(meta, "allocationEscrowed");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.creator.toBase58(), signerAddress.toBase58());
+    assert.deepStrictEqual(Buffer.from(event.claimHash), claimHash(claimKey.publicKey));
+  });
+
+  it("Should not be able to escrow an allocation twice", async () => {
+    await testTransaction([escrowInstruction]);
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([createEscrowAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      claimHash: claimHash(Keypair.generate().publicKey),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to escrow an allocation without holding the nft", async () => {
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 0n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([escrowInstruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to claim an escrowed allocation", async () => {
+    await testTransaction([escrowInstruction]);
+    const meta = await testTransaction(claimInstructions(), claimant);
+    const claimantNftToken = await getTokenAccount(claimantNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(claimantNftToken.amount, 1n);
+    const escrowPromise = getAccount(escrowNftTokenAddress);
+    await assert.rejects(escrowPromise);
+    const claimPromise = getAccount(nftClaimAddress);
+    await assert.rejects(claimPromise);
+    const event = getEmittedEvent(meta, "allocationClaimed");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.claimant.toBase58(), claimant.publicKey.toBase58());
+  });
+
+  it("Should not be able to claim an escrowed allocation with the wrong claim key", async () => {
+    await testTransaction([escrowInstruction]);
+    const promise = testTransaction(claimInstructions({ key: Keypair.generate() }), claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an escrowed allocation with a signature for another wallet", async () => {
+    await testTransaction([escrowInstruction]);
+    const message = claimMessage(nftMintAddress, Keypair.generate().publicKey);
+    const promise = testTransaction(claimInstructions({ message }), claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an escrowed allocation with a signature for another escrow", async () => {
+    await testTransaction([escrowInstruction]);
+    const message = claimMessage(Keypair.generate().publicKey, claimant.publicKey);
+    const promise = testTransaction(claimInstructions({ message }), claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an escrowed allocation with only the claimant signed", async () => {
+    await testTransaction([escrowInstruction]);
+    const message = claimant.publicKey.toBuffer();
+    const promise = testTransaction(claimInstructions({ message }), claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an escrowed allocation without a signature", async () => {
+    await testTransaction([escrowInstruction]);
+    const [transfer, , claim] = claimInstructions();
+    const promise = testTransaction([transfer, claim], claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an escrowed allocation twice", async () => {
+    await testTransaction([escrowInstruction]);
+    await testTransaction(claimInstructions(), claimant);
+    const promise = testTransaction(claimInstructions({ lamports: LAMPORTS_PER_SOL / 2 }), claimant);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim an allocation that is not escrowed", async () => {
+    const promise = testTransaction(claimInstructions(), claimant);
+    await assert.rejects(promise);
+  });
+
+});
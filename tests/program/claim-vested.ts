@@ -0,0 +1,163 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, setClock, closeAccount, getEmittedEvent } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createClaimVestedInstruction } from "@/core/instruction";
+import type { VestingSchedule } from "@/core/coder";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
+
+describe("claim_vested", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const signerTokenAddress = associatedTokenAddress(signerAddress, tokenMintAddress);
+  const allocationTokenAddress = associatedTokenAddress(nftAllocationAddress, tokenMintAddress);
+  const feeTokenAddress = associatedTokenAddress(feeConfigAddress, tokenMintAddress);
+  const linearVesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } };
+  const stepVesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 1000n, endTimestamp: 5000n, unlock: { step: { interval: 1000n } } };
+  const instruction = createClaimVestedInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    tokenMint: tokenMintAddress,
+  });
+
+  const setVesting = async (vesting: VestingSchedule | undefined, claimed = 0n): Promise<void> => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      vesting,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n - claimed, claimed },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+  };
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setVesting(linearVesting);
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+  });
+
+  it("Should not release anything before the cliff", async () => {
+    await setClock(1500);
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 0n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[0].claimed, 0n);
+  });
+
+  it("Should be able to claim a linearly vested amount", async () => {
+    await setClock(3000);
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "vestedClaimed");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(event.amount, 500000n);
+    assert.strictEqual(event.excess, 0n);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+    const allocationToken = await getTokenAccount(allocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 500000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].claimed, 500000n);
+  });
+
+  it("Should only release the unclaimed part on a second claim", async () => {
+    await setVesting(linearVesting, 250000n);
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 750000n,
+    });
+    await setClock(3000);
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 250000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].claimed, 500000n);
+  });
+
+  it("Should be able to claim a step vested amount", async () => {
+    await setVesting(stepVesting);
+    await setClock(3500);
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].claimed, 500000n);
+  });
+
+  it("Should be able to claim everything after the end", async () => {
+    await setClock(6000);
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const allocationToken = getTokenAccount(allocationTokenAddress);
+    await assert.rejects(allocationToken);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].claimed, 1000000n);
+  });
+
+  it("Should transfer any excess tokens to the fee account", async () => {
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1100000n,
+    });
+    await setClock(3000);
+    await testTransaction([instruction]);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 100000n);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+  });
+
+  it("Should not be able to claim without a vesting schedule", async () => {
+    await setVesting(undefined);
+    await setClock(3000);
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim if not the holder", async () => {
+    await closeAccount(nftTokenAddress);
+    await setClock(3000);
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+});
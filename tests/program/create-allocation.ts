@@ -0,0 +1,143 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, getTokenMint, getTokenMetadata, getAllocation, setClock, getAccount, getMinimumBalance } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
+import { createCreateAllocationInstruction } from "@/core/instruction";
+import type { AllocationMetadata, VestingSchedule } from "@/core/coder";
+import { allocationAddress, allocationMintAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId } from "@/core/address";
+import { createAssociatedTokenAccountInstruction, createTransferCheckedInstruction, getMintCloseAuthority, getNonTransferable } from "@solana/spl-token";
+
+describe("create_allocation", () => {
+  const nftMintAddress = allocationMintAddress(signerAddress, 0n);
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const metadata: AllocationMetadata = { name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/" };
+  const instruction = (props?: { nonce?: bigint, authority?: PublicKey, vesting?: VestingSchedule, expiresAt?: bigint, soulbound?: boolean }): TransactionInstruction => createCreateAllocationInstruction({
+    payer: signerAddress,
+    nonce: props?.nonce ?? 0n,
+    metadata,
+    authority: props?.authority,
+    vesting: props?.vesting,
+    expiresAt: props?.expiresAt,
+    soulbound: props?.soulbound,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+  });
+
+  it("Should be able to create an allocation", async () => {
+    await testTransaction([instruction()]);
+    const nftMint = await getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftMint.decimals, 0);
+    assert.strictEqual(nftMint.supply, 1n);
+    assert.strictEqual(nftMint.mintAuthority?.toBase58(), feeConfigAddress.toBase58());
+    assert.strictEqual(getMintCloseAuthority(nftMint)?.closeAuthority.toBase58(), feeConfigAddress.toBase58());
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+    assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
+    assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+  });
+
+  it("Should be able to create an allocation when the nft mint address was funded up front", async () => {
+    for (const lamports of [1000, LAMPORTS_PER_SOL]) {
+      const nonce = BigInt(lamports);
+      const address = allocationMintAddress(signerAddress, nonce);
+      await testTransaction([SystemProgram.transfer({ fromPubkey: signerAddress, toPubkey: address, lamports })]);
+      await testTransaction([instruction({ nonce })]);
+      const nftMint = await getTokenMint(address, tokenExtensionsProgramId);
+      assert.strictEqual(nftMint.supply, 1n);
+      const account = await getAccount(address);
+      assert.strictEqual(account.owner.toBase58(), tokenExtensionsProgramId.toBase58());
+      assert.ok(account.lamports >= await getMinimumBalance(account.data.length));
+    }
+  });
+
+  it("Should set the nft metadata when creating an allocation", async () => {
+    await testTransaction([instruction()]);
+    const tokenMetadata = await getTokenMetadata(nftMintAddress);
+    assert.strictEqual(tokenMetadata.updateAuthority?.toBase58(), feeConfigAddress.toBase58());
+    assert.strictEqual(tokenMetadata.name, metadata.name);
+    assert.strictEqual(tokenMetadata.symbol, metadata.symbol);
+    assert.strictEqual(tokenMetadata.uri, metadata.uri);
+  });
+
+  it("Should be able to create an allocation with a decrease authority", async () => {
+    const decreaseAuthority = Keypair.generate().publicKey;
+    await testTransaction([instruction({ authority: decreaseAuthority })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
+  });
+
+  it("Should be able to create multiple allocations with different nonces", async () => {
+    await testTransaction([instruction(), instruction({ nonce: 1n })]);
+    const allocation = await getAllocation(allocationAddress(allocationMintAddress(signerAddress, 1n)));
+    assert.strictEqual(allocation.initialized, true);
+  });
+
+  it("Should be able to create an allocation with a vesting schedule and expiry", async () => {
+    await setClock(1000);
+    const vesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } };
+    await testTransaction([instruction({ vesting, expiresAt: 6000n })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.vesting?.endTimestamp, 5000n);
+    assert.strictEqual(allocation.expiresAt, 6000n);
+  });
+
+  it("Should be able to transfer a regular allocation nft", async () => {
+    await testTransaction([instruction()]);
+    const nftMint = await getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(getNonTransferable(nftMint), null);
+    const receiver = Keypair.generate().publicKey;
+    const receiverToken = associatedTokenAddress(receiver, nftMintAddress, tokenExtensionsProgramId);
+    await testTransaction([
+      createAssociatedTokenAccountInstruction(signerAddress, receiverToken, receiver, nftMintAddress, tokenExtensionsProgramId),
+      createTransferCheckedInstruction(nftTokenAddress, nftMintAddress, receiverToken, signerAddress, 1n, 0, [], tokenExtensionsProgramId),
+    ]);
+    const nftToken = await getTokenAccount(receiverToken, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+  });
+
+  it("Should be able to create a soulbound allocation", async () => {
+    await testTransaction([instruction({ soulbound: true })]);
+    const nftMint = await getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    assert.notStrictEqual(getNonTransferable(nftMint), null);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+  });
+
+  it("Should not be able to transfer a soulbound allocation nft", async () => {
+    await testTransaction([instruction({ soulbound: true })]);
+    const receiver = Keypair.generate().publicKey;
+    const receiverToken = associatedTokenAddress(receiver, nftMintAddress, tokenExtensionsProgramId);
+    const promise = testTransaction([
+      createAssociatedTokenAccountInstruction(signerAddress, receiverToken, receiver, nftMintAddress, tokenExtensionsProgramId),
+      createTransferCheckedInstruction(nftTokenAddress, nftMintAddress, receiverToken, signerAddress, 1n, 0, [], tokenExtensionsProgramId),
+    ]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to create an allocation with an invalid vesting schedule", async () => {
+    const vesting: VestingSchedule = { startTimestamp: 5000n, cliffTimestamp: 2000n, endTimestamp: 1000n, unlock: { linear: {} } };
+    const promise = testTransaction([instruction({ vesting })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to create an allocation with a used nonce", async () => {
+    await testTransaction([instruction()]);
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to create an allocation with an uninitialized fee config", async () => {
+    await setFeeConfig({ initialized: false });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+});
@@ -1,9 +1,10 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation, getAccount, getTokenMetadata, getEmittedEvent, setAllowedMint, setTransferFeeMint, getMinimumBalance } from "@/tests/program";
 import { Keypair } from "@solana/web3.js";
-import { createDecreaseAllocationInstruction } from "@/core/instruction";
-import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
+import { ACCOUNT_SIZE } from "@solana/spl-token";
+import { createDecreaseAllocationInstruction, createIncreaseAllocationInstruction } from "@/core/instruction";
+import { allocationAddress, allocationUnwrapAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
 
 describe("decrease_allocation", () => {
   const nftMintAddress = Keypair.generate().publicKey;
@@ -25,8 +26,11 @@ describe("decrease_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: tokenMintAddress,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     await setTokenMint({
       address: nftMintAddress,
@@ -55,12 +59,42 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 500000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+  });
+
+  it("Should be able to decrease a transfer fee slot to zero", async () => {
+    const transferFeeMint = Keypair.generate();
+    const transferFeeSignerTokenAddress = associatedTokenAddress(signerAddress, transferFeeMint.publicKey, tokenExtensionsProgramId);
+    const transferFeeAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, transferFeeMint.publicKey, tokenExtensionsProgramId);
+    await setAllocation({ nftMint: nftMintAddress, decreaseAuthority: signerAddress, tokens: [] });
+    await setAllowedMint({ mint: transferFeeMint.publicKey });
+    await setTransferFeeMint({ address: transferFeeMint, feeBps: 100, supply: 1000000n });
+    await testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: transferFeeMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    const allocationBefore = await getAllocation(nftAllocationAddress);
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: transferFeeMint.publicKey,
+      amount: allocationBefore.tokens[0].amount,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    const signerToken = await getTokenAccount(transferFeeSignerTokenAddress, tokenExtensionsProgramId);
+    assert.ok(signerToken.amount > 0n);
+    const allocationToken = await getTokenAccount(transferFeeAllocationTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(allocationToken.amount, 0n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
   });
 
   it("Should be able to decrease allocation with existing atas", async () => {
@@ -82,12 +116,12 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 500000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to decrease allocation with a token surplus", async () => {
@@ -105,20 +139,23 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 500000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 500000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to decrease allocation with token is slot 2", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      secondTokenMint: tokenMintAddress,
-      secondTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const signerToken = await getTokenAccount(signerTokenAddress);
@@ -128,20 +165,23 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 500000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 500000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to decrease allocation with token in slot 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      thirdTokenMint: tokenMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint },
+        { mint: tokenMintAddress, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction]);
     const signerToken = await getTokenAccount(signerTokenAddress);
@@ -151,20 +191,23 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 500000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 500000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), tokenMintAddress.toBase58());
   });
 
   it("Should be able to decrease the full allocation", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: tokenMintAddress,
-      firstTokenAmount: 500000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 500000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     await setTokenAccount({
       address: allocationTokenAddress,
@@ -180,12 +223,12 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to decrease a burned allocation", async () => {
@@ -202,19 +245,188 @@ describe("decrease_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 500000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+  });
+
+  it("Should be able to decrease allocation and unwrap native sol", async () => {
+    const solSignerTokenAddress = associatedTokenAddress(signerAddress, solMint);
+    const solAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, solMint);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    await setTokenAccount({
+      address: solAllocationTokenAddress,
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    const signerBefore = await getAccount(signerAddress);
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: solMint,
+      amount: 500000n,
+      unwrap: true,
+    })]);
+    const signerToken = getTokenAccount(solSignerTokenAddress);
+    await assert.rejects(signerToken);
+    const unwrapToken = getTokenAccount(allocationUnwrapAddress(nftMintAddress));
+    await assert.rejects(unwrapToken);
+    const signerAfter = await getAccount(signerAddress);
+    assert.ok(signerAfter.lamports > signerBefore.lamports);
+    const allocationToken = await getTokenAccount(solAllocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 500000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+  });
+
+  it("Should be able to unwrap native sol to the signer as recipient", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [{ mint: solMint, amount: 1000000n }],
+    });
+    await setTokenAccount({
+      address: associatedTokenAddress(nftAllocationAddress, solMint),
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: solMint,
+      amount: 500000n,
+      unwrap: true,
+      recipient: signerAddress,
+    })]);
+    const signerToken = getTokenAccount(associatedTokenAddress(signerAddress, solMint));
+    await assert.rejects(signerToken);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+  });
+
+  it("Should not unwrap native sol the signer already holds when unwrapping", async () => {
+    const solSignerTokenAddress = associatedTokenAddress(signerAddress, solMint);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [{ mint: solMint, amount: 1000000n }],
+    });
+    await setTokenAccount({
+      address: associatedTokenAddress(nftAllocationAddress, solMint),
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await setTokenAccount({
+      address: solSignerTokenAddress,
+      mint: solMint,
+      authority: signerAddress,
+      amount: 2000000n,
+    });
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: solMint,
+      amount: 500000n,
+      unwrap: true,
+    })]);
+    const signerToken = await getTokenAccount(solSignerTokenAddress);
+    assert.strictEqual(signerToken.amount, 2000000n);
+  });
+
+  it("Should be able to decrease allocation to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+      amount: 500000n,
+      recipient,
+    })]);
+    const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, tokenMintAddress));
+    assert.strictEqual(recipientToken.amount, 500000n);
+    const signerToken = getTokenAccount(signerTokenAddress);
+    await assert.rejects(signerToken);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+  });
+
+  it("Should be able to unwrap native sol to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [{ mint: solMint, amount: 1000000n }],
+    });
+    await setTokenAccount({
+      address: associatedTokenAddress(nftAllocationAddress, solMint),
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: solMint,
+      amount: 500000n,
+      unwrap: true,
+      recipient,
+    })]);
+    const recipientAccount = await getAccount(recipient);
+    const rent = await getMinimumBalance(ACCOUNT_SIZE);
+    assert.strictEqual(recipientAccount.lamports, 500000 + rent);
+    const recipientToken = getTokenAccount(associatedTokenAddress(recipient, solMint));
+    await assert.rejects(recipientToken);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+  });
+
+  it("Should update the nft metadata when decreasing an allocation", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 0n,
+      programId: tokenExtensionsProgramId,
+      metadata: { mint: nftMintAddress, updateAuthority: feeConfigAddress, name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/", additionalMetadata: [] },
+    });
+    await testTransaction([instruction]);
+    const metadata = await getTokenMetadata(nftMintAddress);
+    assert.deepStrictEqual(metadata.additionalMetadata, [["slot0", `${tokenMintAddress.toBase58()}:500000`]]);
+  });
+
+  it("Should not be able to unwrap a token that is not native sol", async () => {
+    const promise = testTransaction([createDecreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+      amount: 500000n,
+      unwrap: true,
+    })]);
+    await assert.rejects(promise);
   });
 
   it("Should not be able to decrease allocation if not the decrease authority", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenMint: tokenMintAddress,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
@@ -231,8 +443,11 @@ describe("decrease_allocation", () => {
       initialized: false,
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: tokenMintAddress,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
@@ -242,8 +457,11 @@ describe("decrease_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: tokenMintAddress,
-      firstTokenAmount: 100000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 100000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
@@ -253,8 +471,11 @@ describe("decrease_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: Keypair.generate().publicKey,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: Keypair.generate().publicKey, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
@@ -265,4 +486,20 @@ describe("decrease_allocation", () => {
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
   });
+
+  it("Should emit an event when decreasing an allocation", async () => {
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1100000n,
+    });
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allocationDecreased");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(event.amount, 500000n);
+    assert.strictEqual(event.excess, 100000n);
+  });
+
 });
@@ -0,0 +1,197 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, getTokenMint, setClock, getEmittedEvents, getAccount } from "@/tests/program";
+import type { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { Keypair } from "@solana/web3.js";
+import { createExerciseAllocationBatchInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
+
+describe("exercise_allocation_batch", () => {
+  const firstNftMintAddress = Keypair.generate().publicKey;
+  const firstNftTokenAddress = associatedTokenAddress(signerAddress, firstNftMintAddress, tokenExtensionsProgramId);
+  const firstNftAllocationAddress = allocationAddress(firstNftMintAddress);
+  const secondNftMintAddress = Keypair.generate().publicKey;
+  const secondNftTokenAddress = associatedTokenAddress(signerAddress, secondNftMintAddress, tokenExtensionsProgramId);
+  const secondNftAllocationAddress = allocationAddress(secondNftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const signerTokenAddress = associatedTokenAddress(signerAddress, tokenMintAddress);
+  const feeTokenAddress = associatedTokenAddress(feeConfigAddress, tokenMintAddress);
+  const secondTokenMintAddress = Keypair.generate().publicKey;
+  const instruction = (props?: { nftMints?: Array<PublicKey>, tokenMints?: Array<PublicKey>, recipient?: PublicKey }): TransactionInstruction => createExerciseAllocationBatchInstruction({
+    payer: signerAddress,
+    allocations: (props?.nftMints ?? [firstNftMintAddress, secondNftMintAddress]).map(nftMint => ({
+      nftMint,
+      tokens: (props?.tokenMints ?? [tokenMintAddress]).map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })),
+    recipient: props?.recipient,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 3000000n,
+      decimals: 2,
+    });
+    for (const [nftMint, nftToken, amount] of [[firstNftMintAddress, firstNftTokenAddress, 1000000n], [secondNftMintAddress, secondNftTokenAddress, 2000000n]] as const) {
+      await setAllocation({
+        nftMint,
+        tokens: [{ mint: tokenMintAddress, amount }],
+      });
+      await setTokenMint({
+        address: nftMint,
+        authority: feeConfigAddress,
+        mintCloseAuthority: feeConfigAddress,
+        supply: 1n,
+        programId: tokenExtensionsProgramId,
+      });
+      await setTokenAccount({
+        address: nftToken,
+        mint: nftMint,
+        authority: signerAddress,
+        amount: 1n,
+        programId: tokenExtensionsProgramId,
+      });
+      await setTokenAccount({
+        address: associatedTokenAddress(allocationAddress(nftMint), tokenMintAddress),
+        mint: tokenMintAddress,
+        authority: allocationAddress(nftMint),
+        amount,
+      });
+    }
+  });
+
+  it("Should be able to exercise multiple allocations", async () => {
+    await testTransaction([instruction()]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 3000000n);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 0n);
+    for (const [nftMint, nftToken, allocation] of [[firstNftMintAddress, firstNftTokenAddress, firstNftAllocationAddress], [secondNftMintAddress, secondNftTokenAddress, secondNftAllocationAddress]]) {
+      await assert.rejects(getTokenMint(nftMint, tokenExtensionsProgramId));
+      await assert.rejects(getTokenAccount(nftToken, tokenExtensionsProgramId));
+      await assert.rejects(getAllocation(allocation));
+    }
+  });
+
+  it("Should be able to exercise a single allocation", async () => {
+    await testTransaction([instruction({ nftMints: [secondNftMintAddress] })]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 2000000n);
+    const allocation = await getAllocation(firstNftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+  });
+
+  it("Should be able to exercise multiple allocations to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await testTransaction([instruction({ recipient })]);
+    const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, tokenMintAddress));
+    assert.strictEqual(recipientToken.amount, 3000000n);
+    await assert.rejects(getTokenAccount(signerTokenAddress));
+    for (const allocation of [firstNftAllocationAddress, secondNftAllocationAddress]) {
+      await assert.rejects(getAllocation(allocation));
+    }
+  });
+
+  it("Should be able to exercise multiple allocations with multiple slots", async () => {
+    await setTokenMint({
+      address: secondTokenMintAddress,
+      supply: 3000n,
+      decimals: 2,
+    });
+    for (const [nftMint, amount, secondAmount] of [[firstNftMintAddress, 1000000n, 1000n], [secondNftMintAddress, 2000000n, 2000n]] as const) {
+      await setAllocation({
+        nftMint,
+        tokens: [{ mint: tokenMintAddress, amount }, { mint: secondTokenMintAddress, amount: secondAmount }],
+      });
+      await setTokenAccount({
+        address: associatedTokenAddress(allocationAddress(nftMint), secondTokenMintAddress),
+        mint: secondTokenMintAddress,
+        authority: allocationAddress(nftMint),
+        amount: secondAmount,
+      });
+    }
+    const recipient = Keypair.generate().publicKey;
+    await testTransaction([instruction({ tokenMints: [tokenMintAddress, secondTokenMintAddress], recipient })]);
+    const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, tokenMintAddress));
+    assert.strictEqual(recipientToken.amount, 3000000n);
+    const secondRecipientToken = await getTokenAccount(associatedTokenAddress(recipient, secondTokenMintAddress));
+    assert.strictEqual(secondRecipientToken.amount, 3000n);
+    for (const allocation of [firstNftAllocationAddress, secondNftAllocationAddress]) {
+      await assert.rejects(getAllocation(allocation));
+    }
+  });
+
+  it("Should be able to exercise multiple allocations and unwrap native sol to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    for (const [nftMint, amount] of [[firstNftMintAddress, 1000000n], [secondNftMintAddress, 2000000n]] as const) {
+      await setAllocation({
+        nftMint,
+        tokens: [{ mint: solMint, amount }],
+      });
+      await setTokenAccount({
+        address: associatedTokenAddress(allocationAddress(nftMint), solMint),
+        mint: solMint,
+        authority: allocationAddress(nftMint),
+        amount,
+      });
+    }
+    await testTransaction([createExerciseAllocationBatchInstruction({
+      payer: signerAddress,
+      allocations: [firstNftMintAddress, secondNftMintAddress].map(nftMint => ({
+        nftMint,
+        tokens: [{ mint: solMint, tokenProgram: tokenProgramId }],
+      })),
+      unwrap: true,
+      recipient,
+    })]);
+    const recipientAccount = await getAccount(recipient);
+    assert.ok(recipientAccount.lamports >= 3000000);
+    const recipientToken = getTokenAccount(associatedTokenAddress(recipient, solMint));
+    await assert.rejects(recipientToken);
+  });
+
+  it("Should emit an event for each allocation when exercising multiple allocations", async () => {
+    const meta = await testTransaction([instruction()]);
+    const events = getEmittedEvents(meta, "allocationExercised");
+    assert.strictEqual(events.length, 2);
+    for (const [event, nftMint, amount] of [[events[0], firstNftMintAddress, 1000000n], [events[1], secondNftMintAddress, 2000000n]] as const) {
+      assert.strictEqual(event.nftMint.toBase58(), nftMint.toBase58());
+      assert.strictEqual(event.owner.toBase58(), signerAddress.toBase58());
+      assert.strictEqual(event.tokens.length, 1);
+      assert.strictEqual(event.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+      assert.strictEqual(event.tokens[0].amount, amount);
+      assert.strictEqual(event.tokens[0].excess, 0n);
+    }
+  });
+
+  it("Should not be able to exercise multiple allocations if one is not vested", async () => {
+    await setClock(1000);
+    await setAllocation({
+      nftMint: secondNftMintAddress,
+      vesting: { startTimestamp: 0n, cliffTimestamp: 0n, endTimestamp: 2000n, unlock: { linear: {} } },
+      tokens: [{ mint: tokenMintAddress, amount: 2000000n }],
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to exercise multiple allocations without holding every nft", async () => {
+    await setTokenAccount({
+      address: secondNftTokenAddress,
+      mint: secondNftMintAddress,
+      authority: Keypair.generate().publicKey,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to exercise without any allocations", async () => {
+    const promise = testTransaction([instruction({ nftMints: [] })]);
+    await assert.rejects(promise);
+  });
+
+});
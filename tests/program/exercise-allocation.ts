@@ -1,10 +1,10 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation, getTokenMint } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation, getTokenMint, setClock, getAccount, getEmittedEvent, setAllowedMint, setTransferFeeMint, compileTransaction, setLookupTable } from "@/tests/program";
 import type { TransactionInstruction } from "@solana/web3.js";
-import { Keypair, PublicKey } from "@solana/web3.js";
-import { createExerciseAllocationInstruction } from "@/core/instruction";
-import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
+import { ComputeBudgetProgram, Keypair, PACKET_DATA_SIZE, PublicKey, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
+import { createExerciseAllocationInstruction, createIncreaseAllocationInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
 
 describe("exercise_allocation", () => {
   const nftMintAddress = Keypair.generate().publicKey;
@@ -34,9 +34,11 @@ describe("exercise_allocation", () => {
   const instruction = (props?: { noFirst?: boolean, noSecond?: boolean, noThird?: boolean }): TransactionInstruction => createExerciseAllocationInstruction({
     payer: signerAddress,
     nftMint: nftMintAddress,
-    firstTokenMint: props?.noFirst ?? false ? solMint : firstTokenMintAddress,
-    secondTokenMint: props?.noSecond ?? false ? usdcMint : secondTokenMintAddress,
-    thirdTokenMint: props?.noThird ?? false ? usdtMint : thirdTokenMintAddress,
+    tokens: [
+      props?.noFirst ?? false ? solMint : firstTokenMintAddress,
+      props?.noSecond ?? false ? usdcMint : secondTokenMintAddress,
+      props?.noThird ?? false ? usdtMint : thirdTokenMintAddress,
+    ].map(mint => ({ mint, tokenProgram: tokenProgramId })),
   });
 
   beforeEach(async () => {
@@ -45,12 +47,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 1000000n,
-      secondTokenMint: secondTokenMintAddress,
-      secondTokenAmount: 1000000n,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
     });
     await setTokenMint({
       address: nftMintAddress,
@@ -137,6 +138,34 @@ describe("exercise_allocation", () => {
     await assert.rejects(allocation);
   });
 
+  it("Should be able to exercise an allocation with a transfer fee mint", async () => {
+    const transferFeeMint = Keypair.generate();
+    const transferFeeSignerTokenAddress = associatedTokenAddress(signerAddress, transferFeeMint.publicKey, tokenExtensionsProgramId);
+    const transferFeeAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, transferFeeMint.publicKey, tokenExtensionsProgramId);
+    await setAllocation({ nftMint: nftMintAddress, decreaseAuthority: signerAddress, tokens: [] });
+    await setAllowedMint({ mint: transferFeeMint.publicKey });
+    await setTransferFeeMint({ address: transferFeeMint, feeBps: 100, supply: 1000000n });
+    await testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: transferFeeMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    const allocationToken = await getTokenAccount(transferFeeAllocationTokenAddress, tokenExtensionsProgramId);
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [{ mint: transferFeeMint.publicKey, tokenProgram: tokenExtensionsProgramId }],
+    })]);
+    const signerToken = await getTokenAccount(transferFeeSignerTokenAddress, tokenExtensionsProgramId);
+    assert.ok(signerToken.amount > 0n && signerToken.amount < allocationToken.amount);
+    const allocationTokenAfter = await getTokenAccount(transferFeeAllocationTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(allocationTokenAfter.amount, 0n);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+  });
+
   it("Should be able to exercise an allocation with existing atas", async () => {
     await setTokenAccount({
       address: firstSignerTokenAddress,
@@ -231,8 +260,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction({ noSecond: true, noThird: true })]);
     const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
@@ -265,8 +297,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      secondTokenMint: secondTokenMintAddress,
-      secondTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction({ noFirst: true, noThird: true })]);
     const firstSignerToken = await getTokenAccount(solSignerTokenAddress);
@@ -299,8 +334,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction({ noFirst: true, noSecond: true })]);
     const firstSignerToken = await getTokenAccount(solSignerTokenAddress);
@@ -333,10 +371,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 1000000n,
-      secondTokenMint: secondTokenMintAddress,
-      secondTokenAmount: 1000000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction({ noThird: true })]);
     const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
@@ -369,10 +408,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 1000000n,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction({ noSecond: true })]);
     const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
@@ -405,10 +445,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      secondTokenMint: secondTokenMintAddress,
-      secondTokenAmount: 1000000n,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction({ noFirst: true })]);
     const firstSignerToken = await getTokenAccount(solSignerTokenAddress);
@@ -441,12 +482,11 @@ describe("exercise_allocation", () => {
     await setAllocation({
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 500000n,
-      secondTokenMint: secondTokenMintAddress,
-      secondTokenAmount: 500000n,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 500000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 500000n },
+        { mint: thirdTokenMintAddress, amount: 500000n },
+      ],
     });
     await testTransaction([instruction()]);
     const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
@@ -475,6 +515,343 @@ describe("exercise_allocation", () => {
     await assert.rejects(allocation);
   });
 
+  it("Should be able to exercise an allocation with more than three tokens", async () => {
+    const fourthTokenMintAddress = Keypair.generate().publicKey;
+    const fourthSignerTokenAddress = associatedTokenAddress(signerAddress, fourthTokenMintAddress);
+    const fourthAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, fourthTokenMintAddress);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+        { mint: fourthTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setTokenMint({
+      address: fourthTokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setTokenAccount({
+      address: fourthAllocationTokenAddress,
+      mint: fourthTokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [firstTokenMintAddress, secondTokenMintAddress, thirdTokenMintAddress, fourthTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
+    assert.strictEqual(firstSignerToken.amount, 1000000n);
+    const fourthSignerToken = await getTokenAccount(fourthSignerTokenAddress);
+    assert.strictEqual(fourthSignerToken.amount, 1000000n);
+    const fourthAllocationToken = getTokenAccount(fourthAllocationTokenAddress);
+    await assert.rejects(fourthAllocationToken);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+  });
+
+  it("Should be able to exercise an allocation with tokens from both token programs", async () => {
+    const extensionsMintAddress = Keypair.generate().publicKey;
+    const extensionsSignerTokenAddress = associatedTokenAddress(signerAddress, extensionsMintAddress, tokenExtensionsProgramId);
+    const extensionsAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, extensionsMintAddress, tokenExtensionsProgramId);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: extensionsMintAddress, amount: 1000000n, tokenProgram: tokenExtensionsProgramId },
+      ],
+    });
+    await setTokenMint({
+      address: extensionsMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: extensionsAllocationTokenAddress,
+      mint: extensionsMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+      programId: tokenExtensionsProgramId,
+    });
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, tokenProgram: tokenProgramId },
+        { mint: secondTokenMintAddress, tokenProgram: tokenProgramId },
+        { mint: extensionsMintAddress, tokenProgram: tokenExtensionsProgramId },
+      ],
+    })]);
+    const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
+    assert.strictEqual(firstSignerToken.amount, 1000000n);
+    const extensionsSignerToken = await getTokenAccount(extensionsSignerTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(extensionsSignerToken.amount, 1000000n);
+    const extensionsAllocationToken = getTokenAccount(extensionsAllocationTokenAddress, tokenExtensionsProgramId);
+    await assert.rejects(extensionsAllocationToken);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+  });
+
+  it("Should not be able to exercise a token with the wrong token program", async () => {
+    const promise = testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [firstTokenMintAddress, secondTokenMintAddress, thirdTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenExtensionsProgramId })),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to exercise an allocation and unwrap native sol", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setTokenAccount({
+      address: solAllocationTokenAddress,
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    const signerBefore = await getAccount(signerAddress);
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [solMint, secondTokenMintAddress, thirdTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+      unwrap: true,
+    })]);
+    const solSignerToken = getTokenAccount(solSignerTokenAddress);
+    await assert.rejects(solSignerToken);
+    const solAllocationToken = getTokenAccount(solAllocationTokenAddress);
+    await assert.rejects(solAllocationToken);
+    const signerAfter = await getAccount(signerAddress);
+    assert.ok(signerAfter.lamports > signerBefore.lamports);
+    const secondSignerToken = await getTokenAccount(secondSignerTokenAddress);
+    assert.strictEqual(secondSignerToken.amount, 1000000n);
+  });
+
+  it("Should not unwrap native sol the signer already holds when exercising", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [{ mint: solMint, amount: 1000000n }],
+    });
+    await setTokenAccount({
+      address: solAllocationTokenAddress,
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await setTokenAccount({
+      address: solSignerTokenAddress,
+      mint: solMint,
+      authority: signerAddress,
+      amount: 2000000n,
+    });
+    const signerBefore = await getAccount(signerAddress);
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [{ mint: solMint, tokenProgram: tokenProgramId }],
+      unwrap: true,
+      recipient: signerAddress,
+    })]);
+    const solSignerToken = await getTokenAccount(solSignerTokenAddress);
+    assert.strictEqual(solSignerToken.amount, 2000000n);
+    const solAllocationToken = getTokenAccount(solAllocationTokenAddress);
+    await assert.rejects(solAllocationToken);
+    const signerAfter = await getAccount(signerAddress);
+    assert.ok(signerAfter.lamports > signerBefore.lamports);
+  });
+
+  it("Should be able to exercise an allocation with the default max tokens in a single transaction", async () => {
+    const mints = Array.from({ length: 5 }, () => Keypair.generate().publicKey);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: mints.map(mint => ({ mint, amount: 1000000n })),
+    });
+    for (const mint of mints) {
+      await setTokenMint({
+        address: mint,
+        supply: 1000000n,
+        decimals: 2,
+      });
+      await setTokenAccount({
+        address: associatedTokenAddress(nftAllocationAddress, mint),
+        mint,
+        authority: nftAllocationAddress,
+        amount: 1000000n,
+      });
+    }
+    const recipient = Keypair.generate().publicKey;
+    const instructions = [
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 1400000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 1 }),
+      createExerciseAllocationInstruction({
+        payer: signerAddress,
+        nftMint: nftMintAddress,
+        tokens: mints.map(mint => ({ mint, tokenProgram: tokenProgramId })),
+        recipient,
+      }),
+    ];
+    const message = new TransactionMessage({
+      instructions,
+      payerKey: signerAddress,
+      recentBlockhash: PublicKey.default.toBase58(),
+    }).compileToV0Message();
+    const transaction = new VersionedTransaction(message);
+    assert.ok(transaction.serialize().length <= PACKET_DATA_SIZE);
+    await testTransaction(instructions);
+    for (const mint of mints) {
+      const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, mint));
+      assert.strictEqual(recipientToken.amount, 1000000n);
+    }
+  });
+
+  it("Should be able to exercise an allocation with the max tokens using a lookup table", async () => {
+    const mints = Array.from({ length: 10 }, () => Keypair.generate().publicKey);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: mints.map(mint => ({ mint, amount: 1000000n })),
+    });
+    for (const mint of mints) {
+      await setTokenMint({
+        address: mint,
+        supply: 1000000n,
+        decimals: 2,
+      });
+      await setTokenAccount({
+        address: associatedTokenAddress(nftAllocationAddress, mint),
+        mint,
+        authority: nftAllocationAddress,
+        amount: 1000000n,
+      });
+    }
+    const recipient = Keypair.generate().publicKey;
+    const instruction = createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: mints.map(mint => ({ mint, tokenProgram: tokenProgramId })),
+      recipient,
+    });
+    const lookupTable = await setLookupTable({
+      address: Keypair.generate().publicKey,
+      addresses: instruction.keys.map(x => x.pubkey).filter(x => !x.equals(signerAddress)),
+    });
+    const transaction = await compileTransaction([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 1400000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 1 }),
+      instruction,
+    ], [lookupTable]);
+    assert.ok(transaction.serialize().length <= PACKET_DATA_SIZE);
+    await testTransaction(transaction);
+    for (const mint of mints) {
+      const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, mint));
+      assert.strictEqual(recipientToken.amount, 1000000n);
+    }
+  });
+
+  it("Should be able to exercise an allocation to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [firstTokenMintAddress, secondTokenMintAddress, thirdTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+      recipient,
+    })]);
+    for (const mint of [firstTokenMintAddress, secondTokenMintAddress, thirdTokenMintAddress]) {
+      const recipientToken = await getTokenAccount(associatedTokenAddress(recipient, mint));
+      assert.strictEqual(recipientToken.amount, 1000000n);
+    }
+    const firstSignerToken = getTokenAccount(firstSignerTokenAddress);
+    await assert.rejects(firstSignerToken);
+    const nftMint = getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    await assert.rejects(nftMint);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+  });
+
+  it("Should be able to exercise an allocation and unwrap native sol to a recipient", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setTokenAccount({
+      address: solAllocationTokenAddress,
+      mint: solMint,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [solMint, secondTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+      unwrap: true,
+      recipient,
+    })]);
+    const recipientAccount = await getAccount(recipient);
+    assert.ok(recipientAccount.lamports >= 1000000);
+    const solRecipientToken = getTokenAccount(associatedTokenAddress(recipient, solMint));
+    await assert.rejects(solRecipientToken);
+    const solAllocationToken = getTokenAccount(solAllocationTokenAddress);
+    await assert.rejects(solAllocationToken);
+    const secondRecipientToken = await getTokenAccount(associatedTokenAddress(recipient, secondTokenMintAddress));
+    assert.strictEqual(secondRecipientToken.amount, 1000000n);
+  });
+
+  it("Should not be able to exercise an allocation with missing token accounts", async () => {
+    const promise = testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [firstTokenMintAddress, secondTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to exercise an allocation with tokens in the wrong order", async () => {
+    const promise = testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [secondTokenMintAddress, firstTokenMintAddress, thirdTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to exercise an allocation before it is fully vested", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      vesting: { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } },
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+        { mint: thirdTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setClock(3000);
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
   it("Should not be able to exercise allocation if not the owner", async () => {
     await closeAccount(nftTokenAddress);
     const promise = testTransaction([instruction()]);
@@ -559,12 +936,11 @@ describe("exercise_allocation", () => {
       initialized: false,
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
-      firstTokenMint: firstTokenMintAddress,
-      firstTokenAmount: 500000n,
-      secondTokenMint: firstTokenMintAddress,
-      secondTokenAmount: 500000n,
-      thirdTokenMint: thirdTokenMintAddress,
-      thirdTokenAmount: 500000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: thirdTokenMintAddress, amount: 500000n },
+      ],
     });
     const promise = testTransaction([instruction()]);
     await assert.rejects(promise);
@@ -597,4 +973,21 @@ describe("exercise_allocation", () => {
     const promise = testTransaction([instruction()]);
     await assert.rejects(promise);
   });
+
+  it("Should emit an event when exercising an allocation", async () => {
+    await setTokenAccount({
+      address: firstAllocationTokenAddress,
+      mint: firstTokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1100000n,
+    });
+    const meta = await testTransaction([instruction()]);
+    const event = getEmittedEvent(meta, "allocationExercised");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.owner.toBase58(), signerAddress.toBase58());
+    assert.deepStrictEqual(event.tokens.map(x => x.mint.toBase58()), [firstTokenMintAddress, secondTokenMintAddress, thirdTokenMintAddress].map(x => x.toBase58()));
+    assert.deepStrictEqual(event.tokens.map(x => x.amount), [1000000n, 1000000n, 1000000n]);
+    assert.deepStrictEqual(event.tokens.map(x => x.excess), [100000n, 0n, 0n]);
+  });
+
 });
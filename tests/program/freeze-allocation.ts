@@ -0,0 +1,180 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, setClock, getEmittedEvent } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createClaimVestedInstruction, createEscrowAllocationInstruction, createExerciseAllocationInstruction, createFreezeAllocationInstruction, createThawAllocationInstruction, createWithdrawFromAllocationInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
+import { claimHash } from "@/core/sign";
+
+describe("freeze_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const holderAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(holderAddress, nftMintAddress, tokenExtensionsProgramId);
+  const freezeInstruction = createFreezeAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    nftToken: nftTokenAddress,
+  });
+  const thawInstruction = createThawAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    nftToken: nftTokenAddress,
+  });
+  const ownerNftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const tokenMintAddress = Keypair.generate().publicKey;
+
+  // Give the signer a frozen nft of an allocation with a single funded slot
+  const setFrozenAllocation = async (): Promise<void> => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      vesting: { startTimestamp: 1000n, cliffTimestamp: 1000n, endTimestamp: 2000n, unlock: { linear: {} } },
+      tokens: [{ mint: tokenMintAddress, amount: 1000000n }],
+    });
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setTokenAccount({
+      address: associatedTokenAddress(allocationAddress(nftMintAddress), tokenMintAddress),
+      mint: tokenMintAddress,
+      authority: allocationAddress(nftMintAddress),
+      amount: 1000000n,
+    });
+    await setTokenAccount({
+      address: ownerNftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    await setClock(3000);
+  };
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      tokens: [],
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: holderAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+  });
+
+  it("Should be able to freeze an allocation nft", async () => {
+    const meta = await testTransaction([freezeInstruction]);
+    const event = getEmittedEvent(meta, "allocationFrozen");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.owner.toBase58(), holderAddress.toBase58());
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.isFrozen, true);
+  });
+
+  it("Should be able to thaw a frozen allocation nft", async () => {
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: holderAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    const meta = await testTransaction([thawInstruction]);
+    const event = getEmittedEvent(meta, "allocationThawed");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.owner.toBase58(), holderAddress.toBase58());
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.isFrozen, false);
+  });
+
+  it("Should not be able to exercise a frozen allocation", async () => {
+    const ownerNftToken = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+    await setTokenAccount({
+      address: ownerNftToken,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    const promise = testTransaction([createExerciseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [],
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw from a frozen allocation", async () => {
+    await setFrozenAllocation();
+    const promise = testTransaction([createWithdrawFromAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+      amount: 500000n,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to claim vested tokens of a frozen allocation", async () => {
+    await setFrozenAllocation();
+    const promise = testTransaction([createClaimVestedInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to escrow a frozen allocation", async () => {
+    await setFrozenAllocation();
+    const promise = testTransaction([createEscrowAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      claimHash: claimHash(Keypair.generate().publicKey),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to freeze an allocation nft if not the recover authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, tokens: [] });
+    const promise = testTransaction([freezeInstruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to thaw an allocation nft if not the recover authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, tokens: [] });
+    const promise = testTransaction([thawInstruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to freeze an allocation nft without a freeze authority", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([freezeInstruction]);
+    await assert.rejects(promise);
+  });
+
+});
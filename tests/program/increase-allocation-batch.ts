@@ -0,0 +1,127 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, setAllowedMint, closeAccount, getEmittedEvents } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair } from "@solana/web3.js";
+import { createIncreaseAllocationBatchInstruction } from "@/core/instruction";
+import { allocationAddress, allowedMintAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
+
+describe("increase_allocation_batch", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const firstTokenMintAddress = Keypair.generate().publicKey;
+  const firstSignerTokenAddress = associatedTokenAddress(signerAddress, firstTokenMintAddress);
+  const firstAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, firstTokenMintAddress);
+  const firstFeeTokenAddress = associatedTokenAddress(feeConfigAddress, firstTokenMintAddress);
+  const secondTokenMintAddress = Keypair.generate().publicKey;
+  const secondSignerTokenAddress = associatedTokenAddress(signerAddress, secondTokenMintAddress);
+  const secondAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, secondTokenMintAddress);
+  const secondFeeTokenAddress = associatedTokenAddress(feeConfigAddress, secondTokenMintAddress);
+  const instruction = (props?: { amounts?: Array<bigint> }): TransactionInstruction => createIncreaseAllocationBatchInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    tokens: [firstTokenMintAddress, secondTokenMintAddress].map((mint, i) => ({
+      mint,
+      tokenProgram: tokenProgramId,
+      amount: props?.amounts?.[i] ?? 1000000n,
+    })),
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({
+      feeBps: 100,
+    });
+    await setAllocation({ nftMint: nftMintAddress, tokens: [] });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    for (const [mint, signerToken] of [[firstTokenMintAddress, firstSignerTokenAddress], [secondTokenMintAddress, secondSignerTokenAddress]]) {
+      await setAllowedMint({ mint });
+      await setTokenMint({
+        address: mint,
+        supply: 1000000n,
+        decimals: 2,
+      });
+      await setTokenAccount({
+        address: signerToken,
+        mint,
+        authority: signerAddress,
+        amount: 1000000n,
+      });
+    }
+  });
+
+  it("Should be able to increase an allocation with multiple tokens", async () => {
+    await testTransaction([instruction()]);
+    const firstAllocationToken = await getTokenAccount(firstAllocationTokenAddress);
+    assert.strictEqual(firstAllocationToken.amount, 990000n);
+    const firstFeeToken = await getTokenAccount(firstFeeTokenAddress);
+    assert.strictEqual(firstFeeToken.amount, 10000n);
+    const secondAllocationToken = await getTokenAccount(secondAllocationTokenAddress);
+    assert.strictEqual(secondAllocationToken.amount, 990000n);
+    const secondFeeToken = await getTokenAccount(secondFeeTokenAddress);
+    assert.strictEqual(secondFeeToken.amount, 10000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 2);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), firstTokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), secondTokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 990000n);
+  });
+
+  it("Should be able to increase an allocation with different amounts", async () => {
+    await testTransaction([instruction({ amounts: [500000n, 100000n] })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 495000n);
+    assert.strictEqual(allocation.tokens[1].amount, 99000n);
+    const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
+    assert.strictEqual(firstSignerToken.amount, 500000n);
+  });
+
+  it("Should emit an event for each token when increasing an allocation", async () => {
+    const meta = await testTransaction([instruction({ amounts: [500000n, 100000n] })]);
+    const events = getEmittedEvents(meta, "allocationIncreased");
+    assert.strictEqual(events.length, 2);
+    for (const [event, mint, gross, fee, net] of [
+      [events[0], firstTokenMintAddress, 500000n, 5000n, 495000n],
+      [events[1], secondTokenMintAddress, 100000n, 1000n, 99000n],
+    ] as const) {
+      assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+      assert.strictEqual(event.mint.toBase58(), mint.toBase58());
+      assert.strictEqual(event.gross, gross);
+      assert.strictEqual(event.fee, fee);
+      assert.strictEqual(event.net, net);
+      assert.strictEqual(event.excess, 0n);
+    }
+  });
+
+  it("Should not be able to increase an allocation with a mint that is not allowed", async () => {
+    await closeAccount(allowedMintAddress(secondTokenMintAddress));
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+    const firstAllocationToken = getTokenAccount(firstAllocationTokenAddress);
+    await assert.rejects(firstAllocationToken);
+  });
+
+  it("Should not be able to increase an allocation with more than the signer has", async () => {
+    const promise = testTransaction([instruction({ amounts: [1000000n, 1000001n] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to increase an allocation past the maximum number of tokens", async () => {
+    await setFeeConfig({ feeBps: 100, maxTokens: 1 });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to increase a restricted allocation without the increase authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, tokens: [], increaseAuthority: Keypair.generate().publicKey });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+});
@@ -1,9 +1,10 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation, setTransferFeeMint, setExtensionMint, getAccount, setAllowedMint, getTokenMetadata, getEmittedEvent } from "@/tests/program";
 import { Keypair } from "@solana/web3.js";
+import { ExtensionType } from "@solana/spl-token";
 import { createIncreaseAllocationInstruction } from "@/core/instruction";
-import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
+import { allocationAddress, allowedMintAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
 
 describe("increase_allocation", () => {
   const nftMintAddress = Keypair.generate().publicKey;
@@ -25,6 +26,7 @@ describe("increase_allocation", () => {
       feeBps: 100,
     });
     await setAllocation({ nftMint: nftMintAddress });
+    await setAllowedMint({ mint: tokenMintAddress });
     await setTokenMint({
       address: nftMintAddress,
       authority: feeConfigAddress,
@@ -53,12 +55,12 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to increase an allocation with existing atas", async () => {
@@ -80,18 +82,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 1", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -101,18 +107,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 1000000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 990000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 2", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      secondTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint, amount: 1000000n },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -122,18 +132,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 1000000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint },
+        { mint: usdtMint, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -143,19 +157,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 1000000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 1 and 2", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenAmount: 1000000n,
-      secondTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint, amount: 1000000n },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -165,19 +182,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 1000000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 1000000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 990000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 990000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), tokenMintAddress.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 1 and 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenAmount: 1000000n,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -187,19 +207,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 1000000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 990000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 1000000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be be able to increase an allocation with a token in slot 2 and 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      secondTokenAmount: 1000000n,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint, amount: 1000000n },
+        { mint: usdtMint, amount: 1000000n },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -209,29 +232,89 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 1000000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 1000000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
-  it("Should not be able to increase an allocation with a token in slot 1, 2 and 3", async () => {
+  it("Should be able to increase an allocation with a token in slot 1, 2 and 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenAmount: 1000000n,
-      secondTokenAmount: 1000000n,
-      thirdTokenAmount: 1000000n,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint, amount: 1000000n },
+        { mint: usdtMint, amount: 1000000n },
+      ],
+    });
+    await testTransaction([instruction]);
+    const allocationToken = await getTokenAccount(allocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 990000n);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 0n);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 10000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 4);
+    assert.strictEqual(allocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 1000000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[3].amount, 990000n);
+    assert.strictEqual(allocation.tokens[3].mint.toBase58(), tokenMintAddress.toBase58());
+  });
+
+  it("Should not be able to increase an allocation with the maximum number of tokens", async () => {
+    await setFeeConfig({ maxTokens: 3 });
+    await setAllocation({
+      nftMint: nftMintAddress,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: usdcMint, amount: 1000000n },
+        { mint: usdtMint, amount: 1000000n },
+      ],
     });
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
   });
 
+  it("Should be able to increase an existing token in an allocation with the maximum number of tokens", async () => {
+    await setFeeConfig({ maxTokens: 3 });
+    await setAllocation({
+      nftMint: nftMintAddress,
+      tokens: [
+        { mint: solMint, amount: 1000000n },
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdtMint, amount: 1000000n },
+      ],
+    });
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await testTransaction([instruction]);
+    const allocationToken = await getTokenAccount(allocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 1990000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 3);
+    assert.strictEqual(allocation.tokens[1].amount, 1990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+  });
+
   it("Should be able to increase an allocation if the token is already in slot 1", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      firstTokenMint: tokenMintAddress,
+      tokens: [
+        { mint: tokenMintAddress },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -241,18 +324,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to increase an allocation if the token is already in slot 2", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      secondTokenMint: tokenMintAddress,
+      tokens: [
+        { mint: solMint },
+        { mint: tokenMintAddress },
+        { mint: usdtMint },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -262,18 +349,22 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 990000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to increase an allocation if the token is already in slot 3", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
-      thirdTokenMint: tokenMintAddress,
+      tokens: [
+        { mint: solMint },
+        { mint: usdcMint },
+        { mint: tokenMintAddress },
+      ],
     });
     await testTransaction([instruction]);
     const allocationToken = await getTokenAccount(allocationTokenAddress);
@@ -283,12 +374,12 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 10000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 990000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 990000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), tokenMintAddress.toBase58());
   });
 
   it("Should be able to increase an allocation if there is a token surplus in the allocation", async () => {
@@ -306,12 +397,220 @@ describe("increase_allocation", () => {
     const feeToken = await getTokenAccount(feeTokenAddress);
     assert.strictEqual(feeToken.amount, 1010000n);
     const allocation = await getAllocation(nftAllocationAddress);
-    assert.strictEqual(allocation.firstTokenAmount, 990000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), tokenMintAddress.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+  });
+
+  it("Should be able to increase an allocation with a token extensions token", async () => {
+    const extensionsMintAddress = Keypair.generate().publicKey;
+    const extensionsSignerTokenAddress = associatedTokenAddress(signerAddress, extensionsMintAddress, tokenExtensionsProgramId);
+    const extensionsAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, extensionsMintAddress, tokenExtensionsProgramId);
+    const extensionsFeeTokenAddress = associatedTokenAddress(feeConfigAddress, extensionsMintAddress, tokenExtensionsProgramId);
+    await setAllowedMint({ mint: extensionsMintAddress });
+    await setTokenMint({
+      address: extensionsMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: extensionsSignerTokenAddress,
+      mint: extensionsMintAddress,
+      authority: signerAddress,
+      amount: 1000000n,
+      programId: tokenExtensionsProgramId,
+    });
+    await testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: extensionsMintAddress,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    const allocationToken = await getTokenAccount(extensionsAllocationTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(allocationToken.amount, 990000n);
+    const feeToken = await getTokenAccount(extensionsFeeTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(feeToken.amount, 10000n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), extensionsMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].tokenProgram.toBase58(), tokenExtensionsProgramId.toBase58());
+    assert.strictEqual(allocation.tokens[1].tokenProgram.toBase58(), tokenProgramId.toBase58());
+  });
+
+  it("Should only credit the received amount for a transfer fee token", async () => {
+    const transferFeeMint = Keypair.generate();
+    const transferFeeAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, transferFeeMint.publicKey, tokenExtensionsProgramId);
+    await setAllowedMint({ mint: transferFeeMint.publicKey });
+    await setTransferFeeMint({
+      address: transferFeeMint,
+      feeBps: 100,
+      supply: 1000000n,
+    });
+    await testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: transferFeeMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    const allocationToken = await getTokenAccount(transferFeeAllocationTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(allocationToken.amount, 980100n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 980100n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), transferFeeMint.publicKey.toBase58());
+  });
+
+  it("Should not be able to add a mint with a permanent delegate to an allocation", async () => {
+    const extensionMint = Keypair.generate();
+    await setAllowedMint({ mint: extensionMint.publicKey });
+    await setExtensionMint({
+      address: extensionMint,
+      extension: ExtensionType.PermanentDelegate,
+      supply: 1000000n,
+    });
+    const promise = testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: extensionMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to add a mint with a transfer hook to an allocation", async () => {
+    const extensionMint = Keypair.generate();
+    await setAllowedMint({ mint: extensionMint.publicKey });
+    await setExtensionMint({
+      address: extensionMint,
+      extension: ExtensionType.TransferHook,
+      supply: 1000000n,
+    });
+    const promise = testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: extensionMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to add a mint with frozen token accounts by default to an allocation", async () => {
+    const extensionMint = Keypair.generate();
+    await setAllowedMint({ mint: extensionMint.publicKey });
+    await setExtensionMint({
+      address: extensionMint,
+      extension: ExtensionType.DefaultAccountState,
+      supply: 1000000n,
+    });
+    const promise = testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: extensionMint.publicKey,
+      amount: 1000000n,
+      tokenProgram: tokenExtensionsProgramId,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to increase an allocation with native sol", async () => {
+    const solSignerTokenAddress = associatedTokenAddress(signerAddress, solMint);
+    const solAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, solMint);
+    const solFeeTokenAddress = associatedTokenAddress(feeConfigAddress, solMint);
+    await setAllowedMint({ mint: solMint });
+    const signerBefore = await getAccount(signerAddress);
+    await testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: solMint,
+      amount: 1000000n,
+      wrap: true,
+    })]);
+    const allocationToken = await getTokenAccount(solAllocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 990000n);
+    const feeToken = await getTokenAccount(solFeeTokenAddress);
+    assert.strictEqual(feeToken.amount, 10000n);
+    const signerToken = getTokenAccount(solSignerTokenAddress);
+    await assert.rejects(signerToken);
+    const signerAfter = await getAccount(signerAddress);
+    assert.ok(signerAfter.lamports < signerBefore.lamports - 1000000);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+  });
+
+  it("Should not be able to wrap a token that is not native sol", async () => {
+    const promise = testTransaction([createIncreaseAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+      amount: 1000000n,
+      wrap: true,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to increase an allocation with a mint that is not allowed", async () => {
+    await closeAccount(allowedMintAddress(tokenMintAddress));
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to increase an allocation with an uninitialized allowed mint", async () => {
+    await setAllowedMint({ mint: tokenMintAddress, initialized: false });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to increase an existing token in a locked allocation", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      locked: true,
+      tokens: [{ mint: solMint }, { mint: tokenMintAddress }, { mint: usdtMint }],
+    });
+    await testTransaction([instruction]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[1].amount, 990000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), tokenMintAddress.toBase58());
+  });
+
+  it("Should not be able to add a new token to a locked allocation", async () => {
+    await setAllocation({ nftMint: nftMintAddress, locked: true });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to increase an allocation as the increase authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, increaseAuthority: signerAddress });
+    await testTransaction([instruction]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 990000n);
+  });
+
+  it("Should not be able to increase an allocation if not the increase authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, increaseAuthority: Keypair.generate().publicKey });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should update the nft metadata when increasing an allocation", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      metadata: { mint: nftMintAddress, updateAuthority: feeConfigAddress, name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/", additionalMetadata: [] },
+    });
+    await testTransaction([instruction]);
+    const metadata = await getTokenMetadata(nftMintAddress);
+    assert.deepStrictEqual(metadata.additionalMetadata, [["slot0", `${tokenMintAddress.toBase58()}:990000`]]);
   });
 
   it("Should not be able to increase a non-existing allocation", async () => {
@@ -344,4 +643,22 @@ describe("increase_allocation", () => {
     const promise = testTransaction([instruction]);
     await assert.rejects(promise);
   });
+
+  it("Should emit an event when increasing an allocation", async () => {
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 5000n,
+    });
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allocationIncreased");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(event.gross, 1000000n);
+    assert.strictEqual(event.fee, 10000n);
+    assert.strictEqual(event.net, 990000n);
+    assert.strictEqual(event.excess, 5000n);
+  });
+
 });
@@ -1,13 +1,16 @@
-import { allocationAddress, feeConfigAddress, jewlProgramId, solMint, systemProgramId, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
+import { allocationAddress, allowedMintAddress, feeConfigAddress, jewlProgramId, solMint, systemProgramId, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
 import type { BanksTransactionMeta, ProgramTestContext } from "solana-bankrun";
 import { Clock, startAnchor } from "solana-bankrun";
 import type { AccountInfo, Signer, Transaction, TransactionInstruction } from "@solana/web3.js";
-import { Keypair, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
+import { AddressLookupTableAccount, AddressLookupTableProgram, Keypair, SystemProgram, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
 import { LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
 import type { Account, Mint } from "@solana/spl-token";
-import { ACCOUNT_SIZE, AccountLayout, AccountState, MINT_SIZE, MintLayout, unpackAccount, unpackMint } from "@solana/spl-token";
-import type { AllocationAccount, FeeConfigAccount } from "@/core/coder";
-import { packAllocation, packFeeConfig, unpackAllocation, unpackFeeConfig } from "@/core/coder";
+import { ACCOUNT_SIZE, AccountLayout, AccountState, ExtensionType, MINT_SIZE, MintLayout, createAssociatedTokenAccountInstruction, createInitializeDefaultAccountStateInstruction, createInitializeMintInstruction, createInitializePermanentDelegateInstruction, createInitializeTransferFeeConfigInstruction, createInitializeTransferHookInstruction, createMintToInstruction, createThawAccountInstruction, getAssociatedTokenAddressSync, getExtensionData, getMintLen, unpackAccount, unpackMint } from "@solana/spl-token";
+import type { AllocationAccount, AllowedMintAccount, FeeConfigAccount, JewlEvent, VestingSchedule } from "@/core/coder";
+import type { TokenMetadata } from "@solana/spl-token-metadata";
+import { pack, unpack } from "@solana/spl-token-metadata";
+import { packAllocation, packAllowedMint, packFeeConfig, unpackAllocation, unpackAllowedMint, unpackEvent, unpackFeeConfig } from "@/core/coder";
+import idl from "@/target/idl/jewl.json";
 
 let context: ProgramTestContext = { } as ProgramTestContext;
 const testSigner = Keypair.generate();
@@ -30,6 +33,10 @@ export interface TokenMintProps {
   decimals?: number;
   programId?: PublicKey;
   mintCloseAuthority?: PublicKey;
+  freezeAuthority?: PublicKey;
+  permanentDelegate?: PublicKey;
+  metadata?: TokenMetadata;
+  nonTransferable?: boolean;
 }
 
 const mintTlvData = Buffer.from([
@@ -40,19 +47,50 @@ const mintTlvData = Buffer.from([
   0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ]);
 
+function permanentDelegateTlvData(delegate: PublicKey): Buffer {
+  const data = Buffer.alloc(4 + 32);
+  data.writeUInt16LE(ExtensionType.PermanentDelegate, 0);
+  data.writeUInt16LE(32, 2);
+  delegate.toBuffer().copy(data, 4);
+  return data;
+}
+
+function nonTransferableTlvData(): Buffer {
+  const data = Buffer.alloc(4);
+  data.writeUInt16LE(ExtensionType.NonTransferable, 0);
+  return data;
+}
+
+function metadataTlvData(address: PublicKey, metadata: TokenMetadata): Buffer {
+  const pointer = Buffer.alloc(4 + 64);
+  pointer.writeUInt16LE(ExtensionType.MetadataPointer, 0);
+  pointer.writeUInt16LE(64, 2);
+  address.toBuffer().copy(pointer, 4 + 32);
+  const data = pack(metadata);
+  const header = Buffer.alloc(4);
+  header.writeUInt16LE(ExtensionType.TokenMetadata, 0);
+  header.writeUInt16LE(data.length, 2);
+  return Buffer.concat([pointer, header, data]);
+}
+
 export async function setTokenMint(props: TokenMintProps): Promise<void> {
   const tokenProgram = props.programId ?? tokenProgramId;
   const hasTlvData = props.mintCloseAuthority != null && props.programId === tokenExtensionsProgramId;
   const tlvData = hasTlvData ? mintTlvData : Buffer.alloc(0);
-  const buffer = Buffer.alloc(MINT_SIZE + tlvData.length);
+  const metadataData = Buffer.concat([
+    hasTlvData && props.permanentDelegate != null ? permanentDelegateTlvData(props.permanentDelegate) : Buffer.alloc(0),
+    hasTlvData && props.nonTransferable === true ? nonTransferableTlvData() : Buffer.alloc(0),
+    hasTlvData && props.metadata != null ? metadataTlvData(props.address, props.metadata) : Buffer.alloc(0),
+  ]);
+  const buffer = Buffer.alloc(MINT_SIZE + tlvData.length + metadataData.length);
   MintLayout.encode({
     mintAuthorityOption: props.authority ? 1 : 0,
     mintAuthority: props.authority ?? PublicKey.default,
     supply: props.supply ?? 0n,
     decimals: props.decimals ?? 0,
     isInitialized: true,
-    freezeAuthorityOption: 0,
-    freezeAuthority: PublicKey.default,
+    freezeAuthorityOption: props.freezeAuthority ? 1 : 0,
+    freezeAuthority: props.freezeAuthority ?? PublicKey.default,
   }, buffer);
 
   if (hasTlvData) {
@@ -61,6 +99,7 @@ export async function setTokenMint(props: TokenMintProps): Promise<void> {
     const offset = MINT_SIZE + tlvData.length - closeData.length;
     tlvData.copy(buffer, MINT_SIZE, 0, tlvData.length);
     closeData.copy(buffer, offset, 0, closeData.length);
+    metadataData.copy(buffer, MINT_SIZE + tlvData.length);
   }
 
   context.setAccount(props.address, {
@@ -72,12 +111,75 @@ export async function setTokenMint(props: TokenMintProps): Promise<void> {
   return Promise.resolve();
 }
 
+interface TransferFeeMintProps {
+  address: Keypair;
+  feeBps: number;
+  supply?: bigint;
+  decimals?: number;
+}
+
+export async function setTransferFeeMint(props: TransferFeeMintProps): Promise<void> {
+  const mint = props.address.publicKey;
+  const space = getMintLen([ExtensionType.TransferFeeConfig]);
+  const signerToken = getAssociatedTokenAddressSync(mint, signerAddress, false, tokenExtensionsProgramId);
+  await testTransaction([
+    SystemProgram.createAccount({
+      fromPubkey: signerAddress,
+      newAccountPubkey: mint,
+      lamports: LAMPORTS_PER_SOL,
+      space,
+      programId: tokenExtensionsProgramId,
+    }),
+    createInitializeTransferFeeConfigInstruction(mint, signerAddress, signerAddress, props.feeBps, BigInt(Number.MAX_SAFE_INTEGER), tokenExtensionsProgramId),
+    createInitializeMintInstruction(mint, props.decimals ?? 2, signerAddress, null, tokenExtensionsProgramId),
+    createAssociatedTokenAccountInstruction(signerAddress, signerToken, signerAddress, mint, tokenExtensionsProgramId),
+    createMintToInstruction(mint, signerToken, signerAddress, props.supply ?? 0n, [], tokenExtensionsProgramId),
+  ], props.address);
+}
+
+interface ExtensionMintProps {
+  address: Keypair;
+  extension: ExtensionType.PermanentDelegate | ExtensionType.TransferHook | ExtensionType.DefaultAccountState;
+  supply?: bigint;
+  decimals?: number;
+}
+
+export async function setExtensionMint(props: ExtensionMintProps): Promise<void> {
+  const mint = props.address.publicKey;
+  const space = getMintLen([props.extension]);
+  const signerToken = getAssociatedTokenAddressSync(mint, signerAddress, false, tokenExtensionsProgramId);
+  const extensionInstructions: Record<ExtensionMintProps["extension"], TransactionInstruction> = {
+    [ExtensionType.PermanentDelegate]: createInitializePermanentDelegateInstruction(mint, signerAddress, tokenExtensionsProgramId),
+    [ExtensionType.TransferHook]: createInitializeTransferHookInstruction(mint, signerAddress, Keypair.generate().publicKey, tokenExtensionsProgramId),
+    [ExtensionType.DefaultAccountState]: createInitializeDefaultAccountStateInstruction(mint, AccountState.Frozen, tokenExtensionsProgramId),
+  };
+  // New token accounts of a default frozen mint have to be thawed before they can receive tokens
+  const thawInstructions = props.extension === ExtensionType.DefaultAccountState
+    ? [createThawAccountInstruction(signerToken, mint, signerAddress, [], tokenExtensionsProgramId)]
+    : [];
+  await testTransaction([
+    SystemProgram.createAccount({
+      fromPubkey: signerAddress,
+      newAccountPubkey: mint,
+      lamports: LAMPORTS_PER_SOL,
+      space,
+      programId: tokenExtensionsProgramId,
+    }),
+    extensionInstructions[props.extension],
+    createInitializeMintInstruction(mint, props.decimals ?? 2, signerAddress, signerAddress, tokenExtensionsProgramId),
+    createAssociatedTokenAccountInstruction(signerAddress, signerToken, signerAddress, mint, tokenExtensionsProgramId),
+    ...thawInstructions,
+    createMintToInstruction(mint, signerToken, signerAddress, props.supply ?? 0n, [], tokenExtensionsProgramId),
+  ], props.address);
+}
+
 interface TokenAccountProps {
   address: PublicKey;
   mint: PublicKey;
   authority: PublicKey;
   amount?: bigint;
   programId?: PublicKey;
+  frozen?: boolean;
 }
 
 export async function setTokenAccount(props: TokenAccountProps): Promise<void> {
@@ -94,7 +196,7 @@ export async function setTokenAccount(props: TokenAccountProps): Promise<void> {
     isNativeOption: props.mint.equals(solMint) ? 1 : 0,
     closeAuthority: props.authority,
     closeAuthorityOption: 1,
-    state: AccountState.Initialized,
+    state: props.frozen === true ? AccountState.Frozen : AccountState.Initialized,
   }, buffer);
 
   context.setAccount(props.address, {
@@ -107,52 +209,149 @@ export async function setTokenAccount(props: TokenAccountProps): Promise<void> {
 }
 
 interface FeeConfigAccountProps {
+  version?: number;
   initialized?: boolean;
   feeAuthority?: PublicKey;
   feeWithdrawAuthority?: PublicKey;
   feeBps?: number;
+  maxTokens?: number;
+  defaultMints?: Array<PublicKey>;
+  lamports?: number;
 }
 
 export async function setFeeConfig(props: FeeConfigAccountProps): Promise<void> {
   const buffer = await packFeeConfig({
+    version: props.version ?? 2,
     initialized: props.initialized ?? true,
     feeAuthority: props.feeAuthority ?? signerAddress,
     feeWithdrawAuthority: props.feeWithdrawAuthority ?? signerAddress,
     feeBps: props.feeBps ?? 100,
+    maxTokens: props.maxTokens ?? 5,
+    reserved: Array(32).fill(0),
+    defaultMints: (props.defaultMints ?? [solMint, usdcMint, usdtMint])
+      .map(mint => ({ mint, tokenProgram: tokenProgramId })),
   });
   context.setAccount(feeConfigAddress, {
     executable: false,
     owner: jewlProgramId,
-    lamports: LAMPORTS_PER_SOL,
+    lamports: props.lamports ?? LAMPORTS_PER_SOL,
     data: buffer,
   });
 }
 
+interface AllocationTokenProps {
+  mint: PublicKey;
+  amount?: bigint;
+  claimed?: bigint;
+  tokenProgram?: PublicKey;
+}
+
 interface AllocationAccountProps {
   nftMint: PublicKey;
+  version?: number;
   initialized?: boolean;
   decreaseAuthority?: PublicKey;
   recoverAuthority?: PublicKey;
-  firstTokenMint?: PublicKey;
-  firstTokenAmount?: bigint;
-  secondTokenMint?: PublicKey;
-  secondTokenAmount?: bigint;
-  thirdTokenMint?: PublicKey;
-  thirdTokenAmount?: bigint;
+  vesting?: VestingSchedule;
+  expiresAt?: bigint;
+  increaseAuthority?: PublicKey;
+  locked?: boolean;
+  tokens?: Array<AllocationTokenProps>;
+  lamports?: number;
 }
 
 export async function setAllocation(props: AllocationAccountProps): Promise<void> {
   const address = allocationAddress(props.nftMint);
   const buffer = await packAllocation({
+    version: props.version ?? 2,
     initialized: props.initialized ?? true,
     decreaseAuthority: props.decreaseAuthority ?? PublicKey.default,
     recoverAuthority: props.recoverAuthority ?? PublicKey.default,
-    firstTokenMint: props.firstTokenMint ?? solMint,
-    firstTokenAmount: props.firstTokenAmount ?? 0n,
-    secondTokenMint: props.secondTokenMint ?? usdcMint,
-    secondTokenAmount: props.secondTokenAmount ?? 0n,
-    thirdTokenMint: props.thirdTokenMint ?? usdtMint,
-    thirdTokenAmount: props.thirdTokenAmount ?? 0n,
+    vesting: props.vesting ?? null,
+    expiresAt: props.expiresAt ?? null,
+    increaseAuthority: props.increaseAuthority ?? null,
+    locked: props.locked ?? false,
+    reserved: Array(32).fill(0),
+    tokens: (props.tokens ?? [{ mint: solMint }, { mint: usdcMint }, { mint: usdtMint }])
+      .map(x => ({ mint: x.mint, amount: x.amount ?? 0n, claimed: x.claimed ?? 0n, tokenProgram: x.tokenProgram ?? tokenProgramId })),
+  });
+  context.setAccount(address, {
+    executable: false,
+    owner: jewlProgramId,
+    lamports: props.lamports ?? LAMPORTS_PER_SOL,
+    data: buffer,
+  });
+}
+
+interface AllowedMintAccountProps {
+  mint: PublicKey;
+  initialized?: boolean;
+}
+
+function accountDiscriminator(name: string): Buffer {
+  const account = idl.accounts.find(x => x.name === name);
+  if (account == null) { throw new Error(`Account ${name} not found`); }
+  return Buffer.from(account.discriminator);
+}
+
+interface LegacyFeeConfigAccountProps {
+  feeAuthority?: PublicKey;
+  feeWithdrawAuthority?: PublicKey;
+  feeBps?: number;
+  lamports?: number;
+}
+
+// Write a fee config in the layout that was deployed before versioning
+export async function setLegacyFeeConfig(props: LegacyFeeConfigAccountProps): Promise<void> {
+  const buffer = Buffer.alloc(8 + 1 + 32 + 32 + 2);
+  accountDiscriminator("FeeConfigAccount").copy(buffer, 0);
+  buffer.writeUInt8(1, 8);
+  (props.feeAuthority ?? signerAddress).toBuffer().copy(buffer, 9);
+  (props.feeWithdrawAuthority ?? signerAddress).toBuffer().copy(buffer, 41);
+  buffer.writeUInt16LE(props.feeBps ?? 100, 73);
+  context.setAccount(feeConfigAddress, {
+    executable: false,
+    owner: jewlProgramId,
+    lamports: props.lamports ?? LAMPORTS_PER_SOL,
+    data: buffer,
+  });
+  return Promise.resolve();
+}
+
+interface LegacyAllocationAccountProps {
+  nftMint: PublicKey;
+  decreaseAuthority?: PublicKey;
+  recoverAuthority?: PublicKey;
+  tokens?: [AllocationTokenProps, AllocationTokenProps, AllocationTokenProps];
+  lamports?: number;
+}
+
+// Write an allocation in the layout that was deployed before versioning with three fixed slots
+export async function setLegacyAllocation(props: LegacyAllocationAccountProps): Promise<void> {
+  const buffer = Buffer.alloc(8 + 1 + 32 + 32 + 3 * (32 + 8));
+  accountDiscriminator("AllocationAccount").copy(buffer, 0);
+  buffer.writeUInt8(1, 8);
+  (props.decreaseAuthority ?? PublicKey.default).toBuffer().copy(buffer, 9);
+  (props.recoverAuthority ?? PublicKey.default).toBuffer().copy(buffer, 41);
+  const tokens = props.tokens ?? [{ mint: solMint }, { mint: usdcMint }, { mint: usdtMint }];
+  tokens.forEach((token, index) => {
+    token.mint.toBuffer().copy(buffer, 73 + index * 40);
+    buffer.writeBigUInt64LE(token.amount ?? 0n, 105 + index * 40);
+  });
+  context.setAccount(allocationAddress(props.nftMint), {
+    executable: false,
+    owner: jewlProgramId,
+    lamports: props.lamports ?? LAMPORTS_PER_SOL,
+    data: buffer,
+  });
+  return Promise.resolve();
+}
+
+export async function setAllowedMint(props: AllowedMintAccountProps): Promise<void> {
+  const address = allowedMintAddress(props.mint);
+  const buffer = await packAllowedMint({
+    initialized: props.initialized ?? true,
+    mint: props.mint,
   });
   context.setAccount(address, {
     executable: false,
@@ -162,6 +361,31 @@ export async function setAllocation(props: AllocationAccountProps): Promise<void
   });
 }
 
+export interface LookupTableAccountProps {
+  address: PublicKey;
+  addresses: Array<PublicKey>;
+}
+
+export async function setLookupTable(props: LookupTableAccountProps): Promise<AddressLookupTableAccount> {
+  // Lookup table meta is a u32 type, u64 deactivation slot, u64 last extended slot, u8 start index, optional authority and padding
+  const buffer = Buffer.alloc(56 + props.addresses.length * 32);
+  buffer.writeUInt32LE(1, 0);
+  buffer.writeBigUInt64LE(BigInt("0xffffffffffffffff"), 4);
+  buffer.writeBigUInt64LE(0n, 12);
+  buffer.writeUInt8(props.addresses.length, 20);
+  props.addresses.forEach((address, i) => address.toBuffer().copy(buffer, 56 + i * 32));
+  context.setAccount(props.address, {
+    executable: false,
+    owner: AddressLookupTableProgram.programId,
+    lamports: LAMPORTS_PER_SOL,
+    data: buffer,
+  });
+  return Promise.resolve(new AddressLookupTableAccount({
+    key: props.address,
+    state: AddressLookupTableAccount.deserialize(buffer),
+  }));
+}
+
 export async function setClock(timestamp: number): Promise<void> {
   const currentClock = await context.banksClient.getClock();
   context.setClock(
@@ -182,11 +406,23 @@ export async function getAccount(address: PublicKey): Promise<AccountInfo<Buffer
   return info;
 }
 
+export async function getMinimumBalance(size: number): Promise<number> {
+  const rent = await context.banksClient.getRent();
+  return Number(rent.minimumBalance(BigInt(size)));
+}
+
 export async function getTokenMint(address: PublicKey, programId = tokenProgramId): Promise<Mint> {
   const account = await getAccount(address);
   return unpackMint(address, account, programId);
 }
 
+export async function getTokenMetadata(address: PublicKey): Promise<TokenMetadata> {
+  const mint = await getTokenMint(address, tokenExtensionsProgramId);
+  const data = getExtensionData(ExtensionType.TokenMetadata, mint.tlvData);
+  if (data == null) { throw new Error(`Mint ${address.toBase58()} has no metadata`); }
+  return unpack(data);
+}
+
 export async function getTokenAccount(address: PublicKey, programId = tokenProgramId): Promise<Account> {
   const account = await getAccount(address);
   return unpackAccount(address, account, programId);
@@ -202,17 +438,26 @@ export async function getAllocation(address: PublicKey): Promise<AllocationAccou
   return unpackAllocation(address, account);
 }
 
+export async function getAllowedMint(address: PublicKey): Promise<AllowedMintAccount> {
+  const account = await getAccount(address);
+  return unpackAllowedMint(address, account);
+}
+
+export async function compileTransaction(instructions: Array<TransactionInstruction>, lookupTables: Array<AddressLookupTableAccount> = []): Promise<VersionedTransaction> {
+  const block = await context.banksClient.getLatestBlockhash();
+  if (block == null) { throw new Error("Failed to get blockhash"); }
+  const message = new TransactionMessage({
+    instructions,
+    payerKey: signerAddress,
+    recentBlockhash: block[0],
+  }).compileToV0Message(lookupTables);
+  return new VersionedTransaction(message);
+}
+
 export async function testTransaction(txOrIxs: Transaction | VersionedTransaction | Array<TransactionInstruction>, ...signers: Array<Signer>): Promise<BanksTransactionMeta> {
   let tx: Transaction | VersionedTransaction;
   if (Array.isArray(txOrIxs)) {
-    const block = await context.banksClient.getLatestBlockhash();
-    if (block == null) { throw new Error("Failed to get blockhash"); }
-    const message = new TransactionMessage({
-      instructions: txOrIxs,
-      payerKey: signerAddress,
-      recentBlockhash: block[0],
-    }).compileToV0Message();
-    tx = new VersionedTransaction(message);
+    tx = await compileTransaction(txOrIxs);
   } else {
     tx = txOrIxs;
   }
@@ -225,6 +470,26 @@ export async function testTransaction(txOrIxs: Transaction | VersionedTransactio
   return context.banksClient.processTransaction(tx);
 }
 
+function unpackEvents(meta: BanksTransactionMeta): Array<JewlEvent> {
+  // Events emitted with emit_cpi show up as an inner self invocation of the program carrying the event
+  return meta.innerInstructions
+    .flat()
+    .map(x => unpackEvent(x.instruction.data))
+    .filter((x): x is JewlEvent => x != null);
+}
+
+export function getEmittedEvents<N extends JewlEvent["name"]>(meta: BanksTransactionMeta, name: N): Array<Extract<JewlEvent, { name: N }>["data"]> {
+  return unpackEvents(meta)
+    .filter((x): x is Extract<JewlEvent, { name: N }> => x.name === name)
+    .map(x => x.data);
+}
+
+export function getEmittedEvent<N extends JewlEvent["name"]>(meta: BanksTransactionMeta, name: N): Extract<JewlEvent, { name: N }>["data"] {
+  const events = getEmittedEvents(meta, name);
+  if (events.length !== 1) { throw new Error(`Expected one ${name} event but found ${events.length}`); }
+  return events[0];
+}
+
 export async function startTestRunner(): Promise<void> {
   const programs = [{ name: "jewl", programId: jewlProgramId }];
   context = await startAnchor(".", programs, [], 1000000n);
@@ -1,19 +1,26 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, setClock, getEmittedEvent } from "@/tests/program";
 import type { TransactionInstruction } from "@solana/web3.js";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { createInitializeAllocationInstruction } from "@/core/instruction";
+import type { AllocationMetadata, VestingSchedule } from "@/core/coder";
+import type { TokenMetadata } from "@solana/spl-token-metadata";
 import { allocationAddress, associatedTokenAddress, feeConfigAddress, solMint, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
 
 describe("initialize_allocation", () => {
   const nftMintAddress = Keypair.generate().publicKey;
   const nftAllocationAddress = allocationAddress(nftMintAddress);
   const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
-  const instruction = (props?: { authority?: PublicKey }): TransactionInstruction => createInitializeAllocationInstruction({
+  const metadata: AllocationMetadata = { name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/" };
+  const tokenMetadata: TokenMetadata = { ...metadata, mint: nftMintAddress, updateAuthority: feeConfigAddress, additionalMetadata: [] };
+  const instruction = (props?: { authority?: PublicKey, vesting?: VestingSchedule, expiresAt?: bigint, metadata?: AllocationMetadata | null }): TransactionInstruction => createInitializeAllocationInstruction({
     payer: signerAddress,
     nftMint: nftMintAddress,
     authority: props?.authority,
+    vesting: props?.vesting,
+    expiresAt: props?.expiresAt,
+    metadata: props?.metadata === null ? undefined : props?.metadata ?? metadata,
   });
 
   beforeEach(async () => {
@@ -23,7 +30,10 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       programId: tokenExtensionsProgramId,
+      metadata: tokenMetadata,
     });
   });
 
@@ -35,12 +45,12 @@ describe("initialize_allocation", () => {
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able initialize allocation with existing ata", async () => {
@@ -57,12 +67,12 @@ describe("initialize_allocation", () => {
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to initialize an uninitialized allocation", async () => {
@@ -77,12 +87,12 @@ describe("initialize_allocation", () => {
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to initialize an allocation with a decrease authority", async () => {
@@ -94,38 +104,112 @@ describe("initialize_allocation", () => {
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+  });
+
+  it("Should be able to initialize an allocation with the configured default mints", async () => {
+    const defaultMintAddress = Keypair.generate().publicKey;
+    await setFeeConfig({ defaultMints: [defaultMintAddress] });
+    await testTransaction([instruction()]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 1);
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), defaultMintAddress.toBase58());
+  });
+
+  it("Should be able to initialize an allocation without default mints", async () => {
+    await setFeeConfig({ defaultMints: [] });
+    await testTransaction([instruction()]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+    assert.strictEqual(allocation.tokens.length, 0);
+  });
+
+  it("Should be able to initialize an allocation with a vesting schedule", async () => {
+    const vesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } };
+    await testTransaction([instruction({ vesting })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+    assert.strictEqual(allocation.vesting?.startTimestamp, 1000n);
+    assert.strictEqual(allocation.vesting?.cliffTimestamp, 2000n);
+    assert.strictEqual(allocation.vesting?.endTimestamp, 5000n);
+    assert.deepStrictEqual(allocation.vesting?.unlock, { linear: {} });
+  });
+
+  it("Should not be able to initialize an allocation with an invalid vesting schedule", async () => {
+    const vesting: VestingSchedule = { startTimestamp: 5000n, cliffTimestamp: 2000n, endTimestamp: 1000n, unlock: { linear: {} } };
+    const promise = testTransaction([instruction({ vesting })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with a zero step interval", async () => {
+    const vesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 1000n, endTimestamp: 5000n, unlock: { step: { interval: 0n } } };
+    const promise = testTransaction([instruction({ vesting })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set a vesting schedule on an existing allocation", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+    });
+    const vesting: VestingSchedule = { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } };
+    const promise = testTransaction([instruction({ metadata: null, vesting })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to initialize an allocation with an expiry", async () => {
+    await setClock(1000);
+    await testTransaction([instruction({ expiresAt: 5000n })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+    assert.strictEqual(allocation.expiresAt, 5000n);
+  });
+
+  it("Should not be able to initialize an allocation with an expiry in the past", async () => {
+    await setClock(5000);
+    const promise = testTransaction([instruction({ expiresAt: 1000n })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set an expiry on an existing allocation", async () => {
+    await setClock(1000);
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+    });
+    const promise = testTransaction([instruction({ metadata: null, expiresAt: 5000n })]);
+    await assert.rejects(promise);
   });
 
   it("Should be able to initialize a burned allocation if the recover authority", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
       recoverAuthority: signerAddress,
-      firstTokenAmount: 100000n,
-      firstTokenMint: usdcMint,
-      secondTokenAmount: 100000n,
-      secondTokenMint: usdtMint,
-      thirdTokenAmount: 100000n,
-      thirdTokenMint: solMint,
-    });
-    await testTransaction([instruction({ authority: signerAddress })]);
+      tokens: [
+        { mint: usdcMint, amount: 100000n },
+        { mint: usdtMint, amount: 100000n },
+        { mint: solMint, amount: 100000n },
+      ],
+    });
+    await testTransaction([instruction({ metadata: null, authority: signerAddress })]);
     const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
     assert.strictEqual(nftToken.amount, 1n);
     const allocation = await getAllocation(nftAllocationAddress);
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 100000n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 100000n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdtMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 100000n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 100000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 100000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 100000n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), solMint.toBase58());
   });
 
   it("Should be able to update the recover authority", async () => {
@@ -134,6 +218,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -141,19 +227,19 @@ describe("initialize_allocation", () => {
       nftMint: nftMintAddress,
       recoverAuthority: signerAddress,
     });
-    await testTransaction([instruction({ authority: recoverAuthority })]);
+    await testTransaction([instruction({ metadata: null, authority: recoverAuthority })]);
     const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
     assert.strictEqual(nftToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), recoverAuthority.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to renounce the recover authority", async () => {
@@ -161,6 +247,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -168,19 +256,19 @@ describe("initialize_allocation", () => {
       nftMint: nftMintAddress,
       recoverAuthority: signerAddress,
     });
-    await testTransaction([instruction()]);
+    await testTransaction([instruction({ metadata: null })]);
     const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
     assert.strictEqual(nftToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), PublicKey.default.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to update the decrease authority", async () => {
@@ -189,6 +277,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -196,19 +286,19 @@ describe("initialize_allocation", () => {
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
     });
-    await testTransaction([instruction({ authority: decreaseAuthority })]);
+    await testTransaction([instruction({ metadata: null, authority: decreaseAuthority })]);
     const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
     assert.strictEqual(nftToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), PublicKey.default.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should be able to renounce the recover authority", async () => {
@@ -216,6 +306,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -223,26 +315,26 @@ describe("initialize_allocation", () => {
       nftMint: nftMintAddress,
       decreaseAuthority: signerAddress,
     });
-    await testTransaction([instruction()]);
+    await testTransaction([instruction({ metadata: null })]);
     const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
     assert.strictEqual(nftToken.amount, 0n);
     const allocation = await getAllocation(nftAllocationAddress);
     assert.strictEqual(allocation.initialized, true);
     assert.strictEqual(allocation.decreaseAuthority.toBase58(), PublicKey.default.toBase58());
     assert.strictEqual(allocation.recoverAuthority.toBase58(), PublicKey.default.toBase58());
-    assert.strictEqual(allocation.firstTokenAmount, 0n);
-    assert.strictEqual(allocation.firstTokenMint.toBase58(), solMint.toBase58());
-    assert.strictEqual(allocation.secondTokenAmount, 0n);
-    assert.strictEqual(allocation.secondTokenMint.toBase58(), usdcMint.toBase58());
-    assert.strictEqual(allocation.thirdTokenAmount, 0n);
-    assert.strictEqual(allocation.thirdTokenMint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), solMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
   });
 
   it("Should not be able to initialize a burned allocation if not the recover authority", async () => {
     await setAllocation({
       nftMint: nftMintAddress,
     });
-    const promise = testTransaction([instruction()]);
+    const promise = testTransaction([instruction({ metadata: null })]);
     await assert.rejects(promise);
   });
 
@@ -251,6 +343,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -264,6 +358,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -276,7 +372,7 @@ describe("initialize_allocation", () => {
       recoverAuthority: signerAddress,
       decreaseAuthority: decreaseAuthority,
     });
-    const promise = testTransaction([instruction({ authority: decreaseAuthority })]);
+    const promise = testTransaction([instruction({ metadata: null, authority: decreaseAuthority })]);
     await assert.rejects(promise);
   });
 
@@ -286,6 +382,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       supply: 1n,
       programId: tokenExtensionsProgramId,
     });
@@ -298,7 +396,77 @@ describe("initialize_allocation", () => {
       recoverAuthority: recoverAuthority,
       decreaseAuthority: signerAddress,
     });
-    const promise = testTransaction([instruction({ authority: recoverAuthority })]);
+    const promise = testTransaction([instruction({ metadata: null, authority: recoverAuthority })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation without metadata", async () => {
+    const promise = testTransaction([instruction({ metadata: null })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with mismatched metadata", async () => {
+    const promise = testTransaction([instruction({ metadata: { ...metadata, name: "Spoofed" } })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with a mint without metadata", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with metadata not owned by the fee config", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      programId: tokenExtensionsProgramId,
+      metadata: { ...tokenMetadata, updateAuthority: signerAddress },
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with pre-seeded slot metadata", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      programId: tokenExtensionsProgramId,
+      metadata: { ...tokenMetadata, additionalMetadata: [["slot0", `${nftMintAddress.toBase58()}:1000000`]] },
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set metadata on an existing allocation", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      metadata: tokenMetadata,
+    });
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+    });
+    const promise = testTransaction([instruction({ authority: Keypair.generate().publicKey })]);
     await assert.rejects(promise);
   });
 
@@ -307,6 +475,8 @@ describe("initialize_allocation", () => {
       address: nftMintAddress,
       authority: feeConfigAddress,
       mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
       decimals: 2,
       programId: tokenExtensionsProgramId,
     });
@@ -344,9 +514,60 @@ describe("initialize_allocation", () => {
     await assert.rejects(promise);
   });
 
+  it("Should not be able to initialize an allocation with the incorrect permanent delegate", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      permanentDelegate: signerAddress,
+      programId: tokenExtensionsProgramId,
+      metadata: tokenMetadata,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation without a permanent delegate", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      programId: tokenExtensionsProgramId,
+      metadata: tokenMetadata,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to initialize an allocation with the incorrect freeze authority", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      freezeAuthority: signerAddress,
+      permanentDelegate: feeConfigAddress,
+      programId: tokenExtensionsProgramId,
+      metadata: tokenMetadata,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
   it("Should not be able to initialize an allocation with an uninitialized fee config", async () => {
     await setFeeConfig({ initialized: false });
     const promise = testTransaction([instruction()]);
     await assert.rejects(promise);
   });
+
+  it("Should emit an event when initializing an allocation", async () => {
+    const decreaseAuthority = Keypair.generate().publicKey;
+    const meta = await testTransaction([instruction({ authority: decreaseAuthority })]);
+    const event = getEmittedEvent(meta, "allocationInitialized");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
+    assert.strictEqual(event.recoverAuthority.toBase58(), signerAddress.toBase58());
+  });
+
 });
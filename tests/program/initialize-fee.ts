@@ -1,20 +1,34 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, getFeeConfig, setFeeConfig } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, getFeeConfig, setFeeConfig, setTokenMint, getEmittedEvent } from "@/tests/program";
 import type { PublicKey, TransactionInstruction } from "@solana/web3.js";
 import { Keypair } from "@solana/web3.js";
 import { createInitializeFeeInstruction } from "@/core/instruction";
+import { tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
 
 describe("initialize_fee", () => {
-  const instruction = (props?: { feeAuthority?: PublicKey, feeWithdrawAuthority?: PublicKey, feeBps?: number }): TransactionInstruction => createInitializeFeeInstruction({
+  const firstMintAddress = Keypair.generate().publicKey;
+  const secondMintAddress = Keypair.generate().publicKey;
+  const instruction = (props?: { feeAuthority?: PublicKey, feeWithdrawAuthority?: PublicKey, feeBps?: number, maxTokens?: number, defaultMints?: Array<PublicKey> }): TransactionInstruction => createInitializeFeeInstruction({
     payer: signerAddress,
     feeAuthority: props?.feeAuthority,
     feeWithdrawAuthority: props?.feeWithdrawAuthority,
     feeBps: props?.feeBps,
+    maxTokens: props?.maxTokens,
+    defaultMints: props?.defaultMints,
   });
 
   beforeEach(async () => {
     await startTestRunner();
+    await setTokenMint({
+      address: firstMintAddress,
+      decimals: 2,
+    });
+    await setTokenMint({
+      address: secondMintAddress,
+      decimals: 2,
+      programId: tokenExtensionsProgramId,
+    });
   });
 
   it("Should be able to initialize fee config", async () => {
@@ -23,6 +37,8 @@ describe("initialize_fee", () => {
     assert.strictEqual(feeConfig.feeAuthority.toBase58(), signerAddress.toBase58());
     assert.strictEqual(feeConfig.feeWithdrawAuthority.toBase58(), signerAddress.toBase58());
     assert.strictEqual(feeConfig.feeBps, 100);
+    assert.strictEqual(feeConfig.maxTokens, 5);
+    assert.strictEqual(feeConfig.defaultMints.length, 0);
   });
 
   it("Should be able to initialize fee config with custom values", async () => {
@@ -82,6 +98,70 @@ describe("initialize_fee", () => {
     assert.strictEqual(feeConfig.feeBps, 200);
   });
 
+  it("Should be able to update only the max tokens", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress });
+    await testTransaction([instruction({ maxTokens: 10 })]);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.feeAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(feeConfig.feeWithdrawAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(feeConfig.feeBps, 100);
+    assert.strictEqual(feeConfig.maxTokens, 10);
+  });
+
+  it("Should not be able to set the max tokens above what fits in an exercise transaction", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress });
+    const promise = testTransaction([instruction({ maxTokens: 11 })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to initialize fee config with default mints", async () => {
+    await testTransaction([instruction({ defaultMints: [firstMintAddress, secondMintAddress] })]);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.defaultMints.length, 2);
+    assert.strictEqual(feeConfig.defaultMints[0].mint.toBase58(), firstMintAddress.toBase58());
+    assert.strictEqual(feeConfig.defaultMints[0].tokenProgram.toBase58(), tokenProgramId.toBase58());
+    assert.strictEqual(feeConfig.defaultMints[1].mint.toBase58(), secondMintAddress.toBase58());
+    assert.strictEqual(feeConfig.defaultMints[1].tokenProgram.toBase58(), tokenExtensionsProgramId.toBase58());
+  });
+
+  it("Should be able to update only the default mints", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress, defaultMints: [] });
+    await testTransaction([instruction({ defaultMints: [firstMintAddress] })]);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.feeBps, 100);
+    assert.strictEqual(feeConfig.defaultMints.length, 1);
+    assert.strictEqual(feeConfig.defaultMints[0].mint.toBase58(), firstMintAddress.toBase58());
+  });
+
+  it("Should be able to clear the default mints", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress });
+    await testTransaction([instruction({ defaultMints: [] })]);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.defaultMints.length, 0);
+  });
+
+  it("Should not be able to set duplicate default mints", async () => {
+    const promise = testTransaction([instruction({ defaultMints: [firstMintAddress, firstMintAddress] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set a default mint that is not a mint", async () => {
+    const promise = testTransaction([instruction({ defaultMints: [Keypair.generate().publicKey] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set more default mints than the max tokens", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress, maxTokens: 1, defaultMints: [] });
+    const promise = testTransaction([instruction({ defaultMints: [firstMintAddress, secondMintAddress] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to set the max tokens below the number of default mints", async () => {
+    await setFeeConfig({ feeAuthority: signerAddress });
+    const promise = testTransaction([instruction({ maxTokens: 2 })]);
+    await assert.rejects(promise);
+  });
+
   it("Should not be able to update without any changes", async () => {
     await setFeeConfig({});
     const promise = testTransaction([instruction()]);
@@ -93,4 +173,16 @@ describe("initialize_fee", () => {
     const promise = testTransaction([instruction({ feeAuthority: signerAddress })]);
     await assert.rejects(promise);
   });
+
+  it("Should emit an event when initializing fee config", async () => {
+    const feeWithdrawAuthority = Keypair.generate().publicKey;
+    const meta = await testTransaction([instruction({ feeWithdrawAuthority, feeBps: 50, maxTokens: 8, defaultMints: [firstMintAddress] })]);
+    const event = getEmittedEvent(meta, "feeConfigUpdated");
+    assert.strictEqual(event.feeAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(event.feeWithdrawAuthority.toBase58(), feeWithdrawAuthority.toBase58());
+    assert.strictEqual(event.feeBps, 50);
+    assert.strictEqual(event.maxTokens, 8);
+    assert.deepStrictEqual(event.defaultMints.map(x => x.toBase58()), [firstMintAddress.toBase58()]);
+  });
+
 });
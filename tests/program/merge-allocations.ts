@@ -0,0 +1,297 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, getTokenMint, getEmittedEvent } from "@/tests/program";
+import type { PublicKey, TransactionInstruction } from "@solana/web3.js";
+import { Keypair } from "@solana/web3.js";
+import { createMergeAllocationsInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
+
+describe("merge_allocations", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const sourceNftMintAddress = Keypair.generate().publicKey;
+  const sourceNftTokenAddress = associatedTokenAddress(signerAddress, sourceNftMintAddress, tokenExtensionsProgramId);
+  const sourceNftAllocationAddress = allocationAddress(sourceNftMintAddress);
+  const firstTokenMintAddress = Keypair.generate().publicKey;
+  const firstAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, firstTokenMintAddress);
+  const firstSourceAllocationTokenAddress = associatedTokenAddress(sourceNftAllocationAddress, firstTokenMintAddress);
+  const secondTokenMintAddress = Keypair.generate().publicKey;
+  const secondAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, secondTokenMintAddress);
+  const secondSourceAllocationTokenAddress = associatedTokenAddress(sourceNftAllocationAddress, secondTokenMintAddress);
+  const secondFeeTokenAddress = associatedTokenAddress(feeConfigAddress, secondTokenMintAddress);
+  const authority = Keypair.generate();
+  const instruction = (props?: { authorities?: Array<PublicKey> }): TransactionInstruction => createMergeAllocationsInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    sourceNftMint: sourceNftMintAddress,
+    tokens: [firstTokenMintAddress, secondTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    authorities: props?.authorities,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    for (const address of [nftMintAddress, sourceNftMintAddress]) {
+      await setTokenMint({
+        address,
+        authority: feeConfigAddress,
+        mintCloseAuthority: feeConfigAddress,
+        supply: 1n,
+        programId: tokenExtensionsProgramId,
+      });
+    }
+    for (const [address, mint] of [[nftTokenAddress, nftMintAddress], [sourceNftTokenAddress, sourceNftMintAddress]]) {
+      await setTokenAccount({
+        address,
+        mint,
+        authority: signerAddress,
+        amount: 1n,
+        programId: tokenExtensionsProgramId,
+      });
+    }
+    await setTokenMint({
+      address: firstTokenMintAddress,
+      supply: 1500000n,
+    });
+    await setTokenAccount({
+      address: firstAllocationTokenAddress,
+      mint: firstTokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await setTokenAccount({
+      address: firstSourceAllocationTokenAddress,
+      mint: firstTokenMintAddress,
+      authority: sourceNftAllocationAddress,
+      amount: 500000n,
+    });
+    await setTokenMint({
+      address: secondTokenMintAddress,
+      supply: 1100000n,
+    });
+    await setTokenAccount({
+      address: secondSourceAllocationTokenAddress,
+      mint: secondTokenMintAddress,
+      authority: sourceNftAllocationAddress,
+      amount: 1100000n,
+    });
+  });
+
+  it("Should be able to merge two allocations", async () => {
+    const meta = await testTransaction([instruction()]);
+    const event = getEmittedEvent(meta, "allocationsMerged");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.sourceNftMint.toBase58(), sourceNftMintAddress.toBase58());
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 2);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), firstTokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1500000n);
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), secondTokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    const firstAllocationToken = await getTokenAccount(firstAllocationTokenAddress);
+    assert.strictEqual(firstAllocationToken.amount, 1500000n);
+    const secondAllocationToken = await getTokenAccount(secondAllocationTokenAddress);
+    assert.strictEqual(secondAllocationToken.amount, 1000000n);
+  });
+
+  it("Should not be able to merge a soulbound allocation into a transferable allocation", async () => {
+    await setTokenMint({
+      address: sourceNftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      nonTransferable: true,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to merge two soulbound allocations", async () => {
+    for (const address of [nftMintAddress, sourceNftMintAddress]) {
+      await setTokenMint({
+        address,
+        authority: feeConfigAddress,
+        mintCloseAuthority: feeConfigAddress,
+        supply: 1n,
+        programId: tokenExtensionsProgramId,
+        nonTransferable: true,
+      });
+    }
+    await testTransaction([instruction()]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 1500000n);
+  });
+
+  it("Should be able to merge a transferable allocation into a soulbound allocation", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      nonTransferable: true,
+    });
+    await testTransaction([instruction()]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 1500000n);
+  });
+
+  it("Should not charge a deposit fee when merging two allocations", async () => {
+    await testTransaction([instruction()]);
+    const secondFeeToken = await getTokenAccount(secondFeeTokenAddress);
+    assert.strictEqual(secondFeeToken.amount, 100000n);
+  });
+
+  it("Should close the source allocation when merging two allocations", async () => {
+    await testTransaction([instruction()]);
+    const sourceAllocation = getAllocation(sourceNftAllocationAddress);
+    await assert.rejects(sourceAllocation);
+    const sourceNftMint = getTokenMint(sourceNftMintAddress, tokenExtensionsProgramId);
+    await assert.rejects(sourceNftMint);
+    const sourceNftToken = getTokenAccount(sourceNftTokenAddress, tokenExtensionsProgramId);
+    await assert.rejects(sourceNftToken);
+    const firstSourceAllocationToken = getTokenAccount(firstSourceAllocationTokenAddress);
+    await assert.rejects(firstSourceAllocationToken);
+    const secondSourceAllocationToken = getTokenAccount(secondSourceAllocationTokenAddress);
+    await assert.rejects(secondSourceAllocationToken);
+  });
+
+  it("Should be able to merge allocations with different authorities if both sign", async () => {
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      decreaseAuthority: authority.publicKey,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority: signerAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await testTransaction([instruction({ authorities: [authority.publicKey] })], authority);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.decreaseAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1500000n);
+  });
+
+  it("Should not be able to merge allocations with different authorities", async () => {
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      decreaseAuthority: authority.publicKey,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to merge allocations with different increase authorities if the set authority signs", async () => {
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      increaseAuthority: authority.publicKey,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await testTransaction([instruction({ authorities: [authority.publicKey] })], authority);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.increaseAuthority, null);
+    assert.strictEqual(allocation.tokens[0].amount, 1500000n);
+  });
+
+  it("Should not be able to merge allocations with different increase authorities", async () => {
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      increaseAuthority: authority.publicKey,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setAllocation({
+      nftMint: nftMintAddress,
+      increaseAuthority: signerAddress,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to merge allocations with a different expiry", async () => {
+    await setAllocation({
+      nftMint: sourceNftMintAddress,
+      expiresAt: 5000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 500000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to merge allocations past the maximum number of tokens", async () => {
+    await setFeeConfig({ maxTokens: 1 });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to merge new tokens into a locked allocation", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      locked: true,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to merge an allocation into itself", async () => {
+    const promise = testTransaction([createMergeAllocationsInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      sourceNftMint: nftMintAddress,
+      tokens: [{ mint: firstTokenMintAddress, tokenProgram: tokenProgramId }],
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to merge an allocation without holding the source nft", async () => {
+    await setTokenAccount({
+      address: sourceNftTokenAddress,
+      mint: sourceNftMintAddress,
+      authority: signerAddress,
+      amount: 0n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+});
@@ -0,0 +1,122 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, setTokenMint, setAllocation, setLegacyAllocation, getAllocation, getAccount, getMinimumBalance, getEmittedEvent, getEmittedEvents } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createMigrateAllocationInstruction, createUpdateAllocationAccessInstruction } from "@/core/instruction";
+import { allocationAddress, feeConfigAddress, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
+
+describe("migrate_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const decreaseAuthority = Keypair.generate().publicKey;
+  const legacySize = 8 + 1 + 32 + 32 + 3 * (32 + 8);
+  const migratedSize = 8 + 1 + 1 + 32 + 32 + 34 + 9 + 33 + 1 + 32 + 4 + 3 * 80;
+  const instruction = createMigrateAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+  });
+  const updateInstruction = createUpdateAllocationAccessInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    locked: true,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setLegacyAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority,
+      recoverAuthority: signerAddress,
+      tokens: [{ mint: tokenMintAddress, amount: 1000n }, { mint: usdcMint, amount: 20n }, { mint: usdtMint }],
+    });
+  });
+
+  it("Should be able to migrate a legacy allocation", async () => {
+    const legacy = await getAccount(nftAllocationAddress);
+    assert.strictEqual(legacy.data.length, legacySize);
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allocationMigrated");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.fromVersion, 1);
+    assert.strictEqual(event.toVersion, 2);
+    const account = await getAccount(nftAllocationAddress);
+    assert.strictEqual(account.data.length, migratedSize);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.version, 2);
+    assert.strictEqual(allocation.initialized, true);
+    assert.strictEqual(allocation.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
+    assert.strictEqual(allocation.recoverAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(allocation.vesting, null);
+    assert.strictEqual(allocation.expiresAt, null);
+    assert.strictEqual(allocation.increaseAuthority, null);
+    assert.strictEqual(allocation.locked, false);
+    assert.strictEqual(allocation.tokens.length, 3);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(allocation.tokens[0].amount, 1000n);
+    assert.strictEqual(allocation.tokens[0].claimed, 0n);
+    assert.strictEqual(allocation.tokens[0].tokenProgram.toBase58(), tokenProgramId.toBase58());
+    assert.strictEqual(allocation.tokens[1].mint.toBase58(), usdcMint.toBase58());
+    assert.strictEqual(allocation.tokens[1].amount, 20n);
+    assert.strictEqual(allocation.tokens[2].mint.toBase58(), usdtMint.toBase58());
+    assert.strictEqual(allocation.tokens[2].amount, 0n);
+  });
+
+  it("Should top up the rent of a migrated allocation", async () => {
+    const legacyRent = await getMinimumBalance(legacySize);
+    await setLegacyAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      lamports: legacyRent,
+    });
+    await testTransaction([instruction]);
+    const account = await getAccount(nftAllocationAddress);
+    assert.strictEqual(account.lamports, await getMinimumBalance(migratedSize));
+    assert.ok(account.lamports > legacyRent);
+  });
+
+  it("Should not change an allocation that is already migrated", async () => {
+    await testTransaction([instruction, instruction]);
+    const account = await getAccount(nftAllocationAddress);
+    assert.strictEqual(account.data.length, migratedSize);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.version, 2);
+    assert.strictEqual(allocation.tokens[0].amount, 1000n);
+  });
+
+  it("Should not change an allocation in the current layout", async () => {
+    await setAllocation({ nftMint: nftMintAddress, recoverAuthority: signerAddress });
+    const before = await getAccount(nftAllocationAddress);
+    const meta = await testTransaction([instruction]);
+    assert.strictEqual(getEmittedEvents(meta, "allocationMigrated").length, 0);
+    const after = await getAccount(nftAllocationAddress);
+    assert.deepStrictEqual(after.data, before.data);
+  });
+
+  it("Should not be able to use a legacy allocation before migrating", async () => {
+    const promise = testTransaction([updateInstruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to use a legacy allocation after migrating", async () => {
+    await testTransaction([instruction, updateInstruction]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.locked, true);
+  });
+
+  it("Should not be able to migrate an allocation that does not exist", async () => {
+    const promise = testTransaction([createMigrateAllocationInstruction({
+      payer: signerAddress,
+      nftMint: Keypair.generate().publicKey,
+    })]);
+    await assert.rejects(promise);
+  });
+
+});
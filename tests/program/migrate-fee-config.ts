@@ -0,0 +1,85 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, setLegacyFeeConfig, getFeeConfig, getAccount, getMinimumBalance, getEmittedEvent, getEmittedEvents } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createInitializeFeeInstruction, createMigrateFeeConfigInstruction } from "@/core/instruction";
+import { feeConfigAddress, solMint, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
+
+describe("migrate_fee_config", () => {
+  const feeWithdrawAuthority = Keypair.generate().publicKey;
+  const legacySize = 8 + 1 + 32 + 32 + 2;
+  const migratedSize = 8 + 1 + 1 + 32 + 32 + 2 + 1 + 32 + 4 + 3 * 64;
+  const instruction = createMigrateFeeConfigInstruction({ payer: signerAddress });
+  const updateInstruction = createInitializeFeeInstruction({ payer: signerAddress, feeBps: 50 });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setLegacyFeeConfig({
+      feeWithdrawAuthority,
+      feeBps: 200,
+    });
+  });
+
+  it("Should be able to migrate a legacy fee config", async () => {
+    const legacy = await getAccount(feeConfigAddress);
+    assert.strictEqual(legacy.data.length, legacySize);
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "feeConfigMigrated");
+    assert.strictEqual(event.fromVersion, 1);
+    assert.strictEqual(event.toVersion, 2);
+    assert.strictEqual(event.feeAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(event.feeWithdrawAuthority.toBase58(), feeWithdrawAuthority.toBase58());
+    assert.strictEqual(event.feeBps, 200);
+    const account = await getAccount(feeConfigAddress);
+    assert.strictEqual(account.data.length, migratedSize);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.version, 2);
+    assert.strictEqual(feeConfig.initialized, true);
+    assert.strictEqual(feeConfig.feeAuthority.toBase58(), signerAddress.toBase58());
+    assert.strictEqual(feeConfig.feeWithdrawAuthority.toBase58(), feeWithdrawAuthority.toBase58());
+    assert.strictEqual(feeConfig.feeBps, 200);
+    assert.strictEqual(feeConfig.maxTokens, 5);
+    assert.deepStrictEqual(feeConfig.defaultMints.map(x => x.mint.toBase58()), [solMint, usdcMint, usdtMint].map(x => x.toBase58()));
+    assert.ok(feeConfig.defaultMints.every(x => x.tokenProgram.equals(tokenProgramId)));
+  });
+
+  it("Should top up the rent of a migrated fee config", async () => {
+    const legacyRent = await getMinimumBalance(legacySize);
+    await setLegacyFeeConfig({ lamports: legacyRent });
+    await testTransaction([instruction]);
+    const account = await getAccount(feeConfigAddress);
+    assert.strictEqual(account.lamports, await getMinimumBalance(migratedSize));
+    assert.ok(account.lamports > legacyRent);
+  });
+
+  it("Should not change a fee config that is already migrated", async () => {
+    await testTransaction([instruction, instruction]);
+    const account = await getAccount(feeConfigAddress);
+    assert.strictEqual(account.data.length, migratedSize);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.version, 2);
+    assert.strictEqual(feeConfig.feeBps, 200);
+  });
+
+  it("Should not change a fee config in the current layout", async () => {
+    await setFeeConfig({});
+    const before = await getAccount(feeConfigAddress);
+    const meta = await testTransaction([instruction]);
+    assert.strictEqual(getEmittedEvents(meta, "feeConfigMigrated").length, 0);
+    const after = await getAccount(feeConfigAddress);
+    assert.deepStrictEqual(after.data, before.data);
+  });
+
+  it("Should not be able to update a legacy fee config before migrating", async () => {
+    const promise = testTransaction([updateInstruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should be able to update a legacy fee config after migrating", async () => {
+    await testTransaction([instruction, updateInstruction]);
+    const feeConfig = await getFeeConfig();
+    assert.strictEqual(feeConfig.feeBps, 50);
+    assert.strictEqual(feeConfig.feeWithdrawAuthority.toBase58(), feeWithdrawAuthority.toBase58());
+  });
+
+});
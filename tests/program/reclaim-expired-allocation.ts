@@ -0,0 +1,231 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, closeAccount, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, getTokenMint, setClock, getEmittedEvent } from "@/tests/program";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createInitializeAllocationInstruction, createReclaimExpiredAllocationInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, tokenProgramId, usdcMint, usdtMint } from "@/core/address";
+
+describe("reclaim_expired_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const signerTokenAddress = associatedTokenAddress(signerAddress, tokenMintAddress);
+  const allocationTokenAddress = associatedTokenAddress(nftAllocationAddress, tokenMintAddress);
+  const feeTokenAddress = associatedTokenAddress(feeConfigAddress, tokenMintAddress);
+  const ownerAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(ownerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const instruction = createReclaimExpiredAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    nftToken: nftTokenAddress,
+    tokens: [tokenMintAddress, usdcMint, usdtMint].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      expiresAt: 5000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: ownerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenMint({
+      address: usdcMint,
+      supply: 0n,
+    });
+    await setTokenMint({
+      address: usdtMint,
+      supply: 0n,
+    });
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await setClock(6000);
+  });
+
+  it("Should be able to reclaim an expired allocation", async () => {
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allocationReclaimed");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.owner?.toBase58(), ownerAddress.toBase58());
+    assert.deepStrictEqual(event.tokens.map(x => x.mint.toBase58()), [tokenMintAddress, usdcMint, usdtMint].map(x => x.toBase58()));
+    assert.deepStrictEqual(event.tokens.map(x => x.amount), [1000000n, 0n, 0n]);
+    assert.deepStrictEqual(event.tokens.map(x => x.excess), [0n, 0n, 0n]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const allocationToken = getTokenAccount(allocationTokenAddress);
+    await assert.rejects(allocationToken);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 0n);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 0n);
+    const nftMint = getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    await assert.rejects(nftMint);
+  });
+
+  it("Should be able to reclaim an expired allocation with a frozen nft", async () => {
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: ownerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    await testTransaction([instruction]);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 0n);
+    const nftMint = getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    await assert.rejects(nftMint);
+  });
+
+  it("Should be able to reclaim an expired allocation after the holder burned the nft", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      supply: 0n,
+      programId: tokenExtensionsProgramId,
+    });
+    await closeAccount(nftTokenAddress);
+    await testTransaction([createReclaimExpiredAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [tokenMintAddress, usdcMint, usdtMint].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+    const nftMint = getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    await assert.rejects(nftMint);
+  });
+
+  it("Should be able to reclaim an expired allocation without burning an nft it cannot burn", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const allocation = getAllocation(nftAllocationAddress);
+    await assert.rejects(allocation);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+  });
+
+  it("Should not be able to reclaim a burnable nft without its token account", async () => {
+    const promise = testTransaction([createReclaimExpiredAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokens: [tokenMintAddress, usdcMint, usdtMint].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should transfer any excess tokens to the fee account", async () => {
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1100000n,
+    });
+    await testTransaction([instruction]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 100000n);
+  });
+
+  it("Should not be able to initialize a reclaimed allocation", async () => {
+    await testTransaction([instruction]);
+    const promise = testTransaction([createInitializeAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to reclaim an allocation before it expires", async () => {
+    await setClock(4000);
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to reclaim an allocation without an expiry", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to reclaim an allocation if not the recover authority", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: PublicKey.default,
+      expiresAt: 5000n,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to reclaim an allocation with missing token accounts", async () => {
+    const promise = testTransaction([createReclaimExpiredAllocationInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      nftToken: nftTokenAddress,
+      tokens: [tokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+    })]);
+    await assert.rejects(promise);
+  });
+});
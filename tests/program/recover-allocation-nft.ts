@@ -0,0 +1,103 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, getTokenMint, setTokenMint, setTokenAccount, setAllocation, getAllocation } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createCreateAllocationInstruction, createRecoverAllocationNftInstruction } from "@/core/instruction";
+import { allocationAddress, allocationMintAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
+import { getPermanentDelegate } from "@solana/spl-token";
+
+describe("recover_allocation_nft", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const oldOwnerAddress = Keypair.generate().publicKey;
+  const newOwnerAddress = Keypair.generate().publicKey;
+  const oldNftTokenAddress = associatedTokenAddress(oldOwnerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const newNftTokenAddress = associatedTokenAddress(newOwnerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const instruction = createRecoverAllocationNftInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    oldNftToken: oldNftTokenAddress,
+    newOwner: newOwnerAddress,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      freezeAuthority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      permanentDelegate: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: oldNftTokenAddress,
+      mint: nftMintAddress,
+      authority: oldOwnerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+  });
+
+  it("Should be able to recover an allocation nft", async () => {
+    await testTransaction([instruction]);
+    const oldNftToken = await getTokenAccount(oldNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(oldNftToken.amount, 0n);
+    const newNftToken = await getTokenAccount(newNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(newNftToken.amount, 1n);
+    const nftMint = await getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftMint.supply, 1n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.initialized, true);
+  });
+
+  it("Should be able to recover a frozen allocation nft", async () => {
+    await setTokenAccount({
+      address: oldNftTokenAddress,
+      mint: nftMintAddress,
+      authority: oldOwnerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    await testTransaction([instruction]);
+    const newNftToken = await getTokenAccount(newNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(newNftToken.amount, 1n);
+  });
+
+  it("Should create allocation nfts with the fee config as permanent delegate", async () => {
+    const createdMintAddress = allocationMintAddress(signerAddress, 0n);
+    await testTransaction([createCreateAllocationInstruction({
+      payer: signerAddress,
+      nonce: 0n,
+      metadata: { name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/" },
+    })]);
+    const nftMint = await getTokenMint(createdMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(getPermanentDelegate(nftMint)?.delegate.toBase58(), feeConfigAddress.toBase58());
+  });
+
+  it("Should not be able to recover an allocation nft if not the recover authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to recover an allocation nft without a permanent delegate", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+});
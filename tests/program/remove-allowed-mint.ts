@@ -0,0 +1,49 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setTokenMint, setFeeConfig, setAllowedMint, getAccount, closeAccount, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createRemoveAllowedMintInstruction } from "@/core/instruction";
+import { allowedMintAddress } from "@/core/address";
+
+describe("remove_allowed_mint", () => {
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const allowedMint = allowedMintAddress(tokenMintAddress);
+  let instruction: TransactionInstruction;
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setAllowedMint({ mint: tokenMintAddress });
+    instruction = createRemoveAllowedMintInstruction({
+      payer: signerAddress,
+      tokenMint: tokenMintAddress,
+    });
+  });
+
+  it("Should be able to remove a mint from the allowlist", async () => {
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "allowedMintRemoved");
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    const promise = getAccount(allowedMint);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to remove a mint that is not allowed", async () => {
+    await closeAccount(allowedMint);
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to remove a mint if not the fee authority", async () => {
+    await setFeeConfig({ feeAuthority: PublicKey.default });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+});
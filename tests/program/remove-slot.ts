@@ -0,0 +1,97 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, setTokenMint, setAllocation, getAllocation, getTokenMetadata, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair } from "@solana/web3.js";
+import { createRemoveSlotInstruction } from "@/core/instruction";
+import { allocationAddress, feeConfigAddress, tokenExtensionsProgramId, usdcMint } from "@/core/address";
+
+describe("remove_slot", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  let instruction: TransactionInstruction;
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      recoverAuthority: signerAddress,
+      tokens: [
+        { mint: tokenMintAddress },
+        { mint: usdcMint, amount: 1000n },
+      ],
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    instruction = createRemoveSlotInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: tokenMintAddress,
+    });
+  });
+
+  it("Should be able to remove an empty slot", async () => {
+    const meta = await testTransaction([instruction]);
+    const event = getEmittedEvent(meta, "slotRemoved");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens.length, 1);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), usdcMint.toBase58());
+  });
+
+  it("Should shift the slot metadata when removing a slot", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      metadata: {
+        name: "Allocation",
+        symbol: "JEWL",
+        uri: "https://jewl.app/",
+        mint: nftMintAddress,
+        updateAuthority: feeConfigAddress,
+        additionalMetadata: [
+          ["slot0", `${tokenMintAddress.toBase58()}:0`],
+          ["slot1", `${usdcMint.toBase58()}:1000`],
+        ],
+      },
+    });
+    await testTransaction([instruction]);
+    const metadata = await getTokenMetadata(nftMintAddress);
+    assert.deepStrictEqual(metadata.additionalMetadata, [["slot0", `${usdcMint.toBase58()}:1000`]]);
+  });
+
+  it("Should not be able to remove a slot that is not empty", async () => {
+    const promise = testTransaction([createRemoveSlotInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: usdcMint,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to remove a slot that does not exist", async () => {
+    const promise = testTransaction([createRemoveSlotInstruction({
+      payer: signerAddress,
+      nftMint: nftMintAddress,
+      tokenMint: Keypair.generate().publicKey,
+    })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to remove a slot if not the recover authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress, tokens: [{ mint: tokenMintAddress }] });
+    const promise = testTransaction([instruction]);
+    await assert.rejects(promise);
+  });
+
+});
@@ -0,0 +1,169 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, getAllocation, getTokenMint, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createSplitAllocationInstruction } from "@/core/instruction";
+import type { AllocationMetadata } from "@/core/coder";
+import { allocationAddress, allocationMintAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, tokenProgramId } from "@/core/address";
+
+describe("split_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const newNftMintAddress = allocationMintAddress(signerAddress, 0n);
+  const newNftTokenAddress = associatedTokenAddress(signerAddress, newNftMintAddress, tokenExtensionsProgramId);
+  const newNftAllocationAddress = allocationAddress(newNftMintAddress);
+  const firstTokenMintAddress = Keypair.generate().publicKey;
+  const firstAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, firstTokenMintAddress);
+  const firstNewAllocationTokenAddress = associatedTokenAddress(newNftAllocationAddress, firstTokenMintAddress);
+  const secondTokenMintAddress = Keypair.generate().publicKey;
+  const secondAllocationTokenAddress = associatedTokenAddress(nftAllocationAddress, secondTokenMintAddress);
+  const secondNewAllocationTokenAddress = associatedTokenAddress(newNftAllocationAddress, secondTokenMintAddress);
+  const decreaseAuthority = Keypair.generate().publicKey;
+  const metadata: AllocationMetadata = { name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/" };
+  const instruction = (props?: { amounts?: Array<bigint> }): TransactionInstruction => createSplitAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    nonce: 0n,
+    amounts: props?.amounts ?? [400000n, 0n],
+    metadata,
+    tokens: [firstTokenMintAddress, secondTokenMintAddress].map(mint => ({ mint, tokenProgram: tokenProgramId })),
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      decreaseAuthority,
+      expiresAt: 5000n,
+      tokens: [
+        { mint: firstTokenMintAddress, amount: 1000000n },
+        { mint: secondTokenMintAddress, amount: 1000000n },
+      ],
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenMint({
+      address: firstTokenMintAddress,
+      supply: 1000000n,
+    });
+    await setTokenAccount({
+      address: firstAllocationTokenAddress,
+      mint: firstTokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+    await setTokenMint({
+      address: secondTokenMintAddress,
+      supply: 1000000n,
+    });
+    await setTokenAccount({
+      address: secondAllocationTokenAddress,
+      mint: secondTokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+  });
+
+  it("Should be able to split an allocation", async () => {
+    const meta = await testTransaction([instruction()]);
+    const event = getEmittedEvent(meta, "allocationSplit");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.newNftMint.toBase58(), newNftMintAddress.toBase58());
+    assert.deepStrictEqual(event.amounts, [400000n, 0n]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 600000n);
+    assert.strictEqual(allocation.tokens[1].amount, 1000000n);
+    const firstAllocationToken = await getTokenAccount(firstAllocationTokenAddress);
+    assert.strictEqual(firstAllocationToken.amount, 600000n);
+    const newAllocation = await getAllocation(newNftAllocationAddress);
+    assert.strictEqual(newAllocation.initialized, true);
+    assert.strictEqual(newAllocation.tokens.length, 2);
+    assert.strictEqual(newAllocation.tokens[0].mint.toBase58(), firstTokenMintAddress.toBase58());
+    assert.strictEqual(newAllocation.tokens[0].amount, 400000n);
+    assert.strictEqual(newAllocation.tokens[1].mint.toBase58(), secondTokenMintAddress.toBase58());
+    assert.strictEqual(newAllocation.tokens[1].amount, 0n);
+    const firstNewAllocationToken = await getTokenAccount(firstNewAllocationTokenAddress);
+    assert.strictEqual(firstNewAllocationToken.amount, 400000n);
+    const secondNewAllocationToken = getTokenAccount(secondNewAllocationTokenAddress);
+    await assert.rejects(secondNewAllocationToken);
+  });
+
+  it("Should mint a new nft to the holder when splitting an allocation", async () => {
+    await testTransaction([instruction()]);
+    const newNftMint = await getTokenMint(newNftMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(newNftMint.supply, 1n);
+    assert.strictEqual(newNftMint.mintAuthority?.toBase58(), feeConfigAddress.toBase58());
+    const newNftToken = await getTokenAccount(newNftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(newNftToken.amount, 1n);
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+  });
+
+  it("Should copy the authorities and schedule when splitting an allocation", async () => {
+    await testTransaction([instruction()]);
+    const newAllocation = await getAllocation(newNftAllocationAddress);
+    assert.strictEqual(newAllocation.decreaseAuthority.toBase58(), decreaseAuthority.toBase58());
+    assert.strictEqual(newAllocation.recoverAuthority.toBase58(), PublicKey.default.toBase58());
+    assert.strictEqual(newAllocation.expiresAt, 5000n);
+  });
+
+  it("Should be able to split the full amount of an allocation", async () => {
+    await testTransaction([instruction({ amounts: [1000000n, 1000000n] })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.tokens[1].amount, 0n);
+    const newAllocation = await getAllocation(newNftAllocationAddress);
+    assert.strictEqual(newAllocation.tokens[0].amount, 1000000n);
+    assert.strictEqual(newAllocation.tokens[1].amount, 1000000n);
+  });
+
+  it("Should not be able to split more than the allocation holds", async () => {
+    const promise = testTransaction([instruction({ amounts: [1000001n, 0n] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to split with a mismatched number of amounts", async () => {
+    const promise = testTransaction([instruction({ amounts: [1000n] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to split an allocation without holding the nft", async () => {
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 0n,
+      programId: tokenExtensionsProgramId,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to split an allocation with a frozen nft", async () => {
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+      frozen: true,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+});
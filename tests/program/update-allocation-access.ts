@@ -0,0 +1,70 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, setTokenMint, setAllocation, getAllocation, getEmittedEvent } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createUpdateAllocationAccessInstruction } from "@/core/instruction";
+import { allocationAddress, feeConfigAddress, tokenExtensionsProgramId } from "@/core/address";
+
+describe("update_allocation_access", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const increaseAuthority = Keypair.generate().publicKey;
+  const instruction = (props?: { locked?: boolean, increaseAuthority?: PublicKey }): TransactionInstruction => createUpdateAllocationAccessInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    locked: props?.locked ?? false,
+    increaseAuthority: props?.increaseAuthority,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({ nftMint: nftMintAddress, recoverAuthority: signerAddress });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+  });
+
+  it("Should be able to lock an allocation", async () => {
+    const meta = await testTransaction([instruction({ locked: true })]);
+    const event = getEmittedEvent(meta, "allocationAccessUpdated");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.locked, true);
+    assert.strictEqual(event.increaseAuthority, null);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.locked, true);
+    assert.strictEqual(allocation.increaseAuthority, null);
+  });
+
+  it("Should be able to set an increase authority", async () => {
+    await testTransaction([instruction({ increaseAuthority })]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.locked, false);
+    assert.strictEqual(allocation.increaseAuthority?.toBase58(), increaseAuthority.toBase58());
+  });
+
+  it("Should be able to unlock an allocation", async () => {
+    await setAllocation({ nftMint: nftMintAddress, recoverAuthority: signerAddress, locked: true, increaseAuthority });
+    await testTransaction([instruction()]);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.locked, false);
+    assert.strictEqual(allocation.increaseAuthority, null);
+  });
+
+  it("Should not be able to update access if not the recover authority", async () => {
+    await setAllocation({ nftMint: nftMintAddress });
+    const promise = testTransaction([instruction({ locked: true })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to update access of an uninitialized allocation", async () => {
+    await setAllocation({ nftMint: nftMintAddress, recoverAuthority: signerAddress, initialized: false });
+    const promise = testTransaction([instruction({ locked: true })]);
+    await assert.rejects(promise);
+  });
+
+});
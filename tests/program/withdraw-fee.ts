@@ -1,6 +1,6 @@
 import { describe, it, beforeEach } from "mocha";
 import assert from "assert";
-import { testTransaction, startTestRunner, signerAddress, setTokenMint, setTokenAccount, getTokenAccount, setFeeConfig } from "@/tests/program";
+import { testTransaction, startTestRunner, signerAddress, setTokenMint, setTokenAccount, getTokenAccount, setFeeConfig, getEmittedEvent } from "@/tests/program";
 import type { TransactionInstruction } from "@solana/web3.js";
 import { Keypair, PublicKey } from "@solana/web3.js";
 import { createWithdrawFeeInstruction } from "@/core/instruction";
@@ -91,4 +91,12 @@ describe("withdraw_fee", () => {
     await assert.rejects(promise);
   });
 
+  it("Should emit an event when withdrawing protocol fees", async () => {
+    const meta = await testTransaction([instruction({ amount: 400000n })]);
+    const event = getEmittedEvent(meta, "feeWithdrawn");
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(event.amount, 400000n);
+    assert.strictEqual(event.destination.toBase58(), signerTokenAddress.toBase58());
+  });
+
 });
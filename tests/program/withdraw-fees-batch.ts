@@ -0,0 +1,111 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setTokenMint, setTokenAccount, getTokenAccount, setFeeConfig, getEmittedEvents } from "@/tests/program";
+import type { TransactionInstruction } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { createWithdrawFeesBatchInstruction } from "@/core/instruction";
+import { associatedTokenAddress, feeConfigAddress, tokenProgramId } from "@/core/address";
+
+describe("withdraw_fees_batch", () => {
+  const firstTokenMintAddress = Keypair.generate().publicKey;
+  const firstFeeTokenAddress = associatedTokenAddress(feeConfigAddress, firstTokenMintAddress);
+  const firstSignerTokenAddress = associatedTokenAddress(signerAddress, firstTokenMintAddress);
+  const secondTokenMintAddress = Keypair.generate().publicKey;
+  const secondFeeTokenAddress = associatedTokenAddress(feeConfigAddress, secondTokenMintAddress);
+  const secondSignerTokenAddress = associatedTokenAddress(signerAddress, secondTokenMintAddress);
+  const instruction = (props?: { amounts?: Array<bigint | undefined>, destination?: PublicKey }): TransactionInstruction => createWithdrawFeesBatchInstruction({
+    payer: signerAddress,
+    destination: props?.destination,
+    tokens: [firstTokenMintAddress, secondTokenMintAddress].map((mint, i) => ({
+      mint,
+      tokenProgram: tokenProgramId,
+      amount: props?.amounts?.[i],
+    })),
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    for (const [mint, feeToken] of [[firstTokenMintAddress, firstFeeTokenAddress], [secondTokenMintAddress, secondFeeTokenAddress]]) {
+      await setTokenMint({
+        address: mint,
+        supply: 1000000n,
+        decimals: 2,
+      });
+      await setTokenAccount({
+        address: feeToken,
+        mint,
+        authority: feeConfigAddress,
+        amount: 1000000n,
+      });
+    }
+  });
+
+  it("Should be able to withdraw protocol fees for multiple mints", async () => {
+    await testTransaction([instruction()]);
+    await assert.rejects(getTokenAccount(firstFeeTokenAddress));
+    await assert.rejects(getTokenAccount(secondFeeTokenAddress));
+    const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
+    assert.strictEqual(firstSignerToken.amount, 1000000n);
+    const secondSignerToken = await getTokenAccount(secondSignerTokenAddress);
+    assert.strictEqual(secondSignerToken.amount, 1000000n);
+  });
+
+  it("Should be able to withdraw partial protocol fees for multiple mints", async () => {
+    await testTransaction([instruction({ amounts: [500000n, undefined] })]);
+    const firstFeeToken = await getTokenAccount(firstFeeTokenAddress);
+    assert.strictEqual(firstFeeToken.amount, 500000n);
+    const firstSignerToken = await getTokenAccount(firstSignerTokenAddress);
+    assert.strictEqual(firstSignerToken.amount, 500000n);
+    await assert.rejects(getTokenAccount(secondFeeTokenAddress));
+  });
+
+  it("Should be able to withdraw protocol fees to another wallet", async () => {
+    const destination = Keypair.generate().publicKey;
+    await testTransaction([instruction({ destination })]);
+    const firstDestinationToken = await getTokenAccount(associatedTokenAddress(destination, firstTokenMintAddress));
+    assert.strictEqual(firstDestinationToken.amount, 1000000n);
+    const secondDestinationToken = await getTokenAccount(associatedTokenAddress(destination, secondTokenMintAddress));
+    assert.strictEqual(secondDestinationToken.amount, 1000000n);
+    await assert.rejects(getTokenAccount(firstSignerTokenAddress));
+  });
+
+  it("Should emit an event for each mint when withdrawing protocol fees for multiple mints", async () => {
+    const meta = await testTransaction([instruction({ amounts: [500000n, undefined] })]);
+    const events = getEmittedEvents(meta, "feeWithdrawn");
+    assert.strictEqual(events.length, 2);
+    for (const [event, mint, amount, destination] of [
+      [events[0], firstTokenMintAddress, 500000n, firstSignerTokenAddress],
+      [events[1], secondTokenMintAddress, 1000000n, secondSignerTokenAddress],
+    ] as const) {
+      assert.strictEqual(event.mint.toBase58(), mint.toBase58());
+      assert.strictEqual(event.amount, amount);
+      assert.strictEqual(event.destination.toBase58(), destination.toBase58());
+    }
+  });
+
+  it("Should not be able to withdraw from a token account not owned by the fee config", async () => {
+    await setTokenAccount({
+      address: secondFeeTokenAddress,
+      mint: secondTokenMintAddress,
+      authority: Keypair.generate().publicKey,
+      amount: 1000000n,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw more than the fee account holds", async () => {
+    const promise = testTransaction([instruction({ amounts: [1000001n, undefined] })]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw if not the withdraw authority", async () => {
+    await setFeeConfig({
+      feeWithdrawAuthority: PublicKey.default,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+});
@@ -0,0 +1,173 @@
+import { describe, it, beforeEach } from "mocha";
+import assert from "assert";
+import { testTransaction, startTestRunner, signerAddress, setFeeConfig, getTokenAccount, setTokenMint, setTokenAccount, setAllocation, closeAccount, getAllocation, getTokenMint, setClock, getTokenMetadata, getEmittedEvent } from "@/tests/program";
+import { Keypair } from "@solana/web3.js";
+import { createWithdrawFromAllocationInstruction } from "@/core/instruction";
+import { allocationAddress, associatedTokenAddress, feeConfigAddress, tokenExtensionsProgramId, usdcMint, usdtMint } from "@/core/address";
+
+describe("withdraw_from_allocation", () => {
+  const nftMintAddress = Keypair.generate().publicKey;
+  const nftTokenAddress = associatedTokenAddress(signerAddress, nftMintAddress, tokenExtensionsProgramId);
+  const nftAllocationAddress = allocationAddress(nftMintAddress);
+  const tokenMintAddress = Keypair.generate().publicKey;
+  const signerTokenAddress = associatedTokenAddress(signerAddress, tokenMintAddress);
+  const allocationTokenAddress = associatedTokenAddress(nftAllocationAddress, tokenMintAddress);
+  const feeTokenAddress = associatedTokenAddress(feeConfigAddress, tokenMintAddress);
+  const instruction = (amount = 500000n) => createWithdrawFromAllocationInstruction({
+    payer: signerAddress,
+    nftMint: nftMintAddress,
+    tokenMint: tokenMintAddress,
+    amount,
+  });
+
+  beforeEach(async () => {
+    await startTestRunner();
+    await setFeeConfig({});
+    await setAllocation({
+      nftMint: nftMintAddress,
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenAccount({
+      address: nftTokenAddress,
+      mint: nftMintAddress,
+      authority: signerAddress,
+      amount: 1n,
+      programId: tokenExtensionsProgramId,
+    });
+    await setTokenMint({
+      address: tokenMintAddress,
+      supply: 1000000n,
+      decimals: 2,
+    });
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1000000n,
+    });
+  });
+
+  it("Should be able to withdraw part of an allocation", async () => {
+    const meta = await testTransaction([instruction()]);
+    const event = getEmittedEvent(meta, "allocationWithdrawn");
+    assert.strictEqual(event.nftMint.toBase58(), nftMintAddress.toBase58());
+    assert.strictEqual(event.mint.toBase58(), tokenMintAddress.toBase58());
+    assert.strictEqual(event.amount, 500000n);
+    assert.strictEqual(event.excess, 0n);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+    const allocationToken = await getTokenAccount(allocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 500000n);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 0n);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 500000n);
+    assert.strictEqual(allocation.tokens[0].mint.toBase58(), tokenMintAddress.toBase58());
+    const nftToken = await getTokenAccount(nftTokenAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftToken.amount, 1n);
+  });
+
+  it("Should update the nft metadata when withdrawing from an allocation", async () => {
+    await setTokenMint({
+      address: nftMintAddress,
+      authority: feeConfigAddress,
+      mintCloseAuthority: feeConfigAddress,
+      supply: 1n,
+      programId: tokenExtensionsProgramId,
+      metadata: { mint: nftMintAddress, updateAuthority: feeConfigAddress, name: "Allocation", symbol: "JEWL", uri: "https://jewl.app/", additionalMetadata: [] },
+    });
+    await testTransaction([instruction()]);
+    const metadata = await getTokenMetadata(nftMintAddress);
+    assert.deepStrictEqual(metadata.additionalMetadata, [["slot0", `${tokenMintAddress.toBase58()}:500000`]]);
+  });
+
+  it("Should be able to withdraw a full token slot", async () => {
+    await testTransaction([instruction(1000000n)]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 1000000n);
+    const allocationToken = getTokenAccount(allocationTokenAddress);
+    await assert.rejects(allocationToken);
+    const allocation = await getAllocation(nftAllocationAddress);
+    assert.strictEqual(allocation.tokens[0].amount, 0n);
+    assert.strictEqual(allocation.initialized, true);
+    const nftMint = await getTokenMint(nftMintAddress, tokenExtensionsProgramId);
+    assert.strictEqual(nftMint.supply, 1n);
+  });
+
+  it("Should be able to withdraw with a token surplus", async () => {
+    await setTokenAccount({
+      address: allocationTokenAddress,
+      mint: tokenMintAddress,
+      authority: nftAllocationAddress,
+      amount: 1500000n,
+    });
+    await testTransaction([instruction()]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+    const allocationToken = await getTokenAccount(allocationTokenAddress);
+    assert.strictEqual(allocationToken.amount, 500000n);
+    const feeToken = await getTokenAccount(feeTokenAddress);
+    assert.strictEqual(feeToken.amount, 500000n);
+  });
+
+  it("Should be able to withdraw from a fully vested allocation", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      vesting: { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } },
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    await setClock(6000);
+    await testTransaction([instruction()]);
+    const signerToken = await getTokenAccount(signerTokenAddress);
+    assert.strictEqual(signerToken.amount, 500000n);
+  });
+
+  it("Should not be able to withdraw from an allocation that is not fully vested", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+      vesting: { startTimestamp: 1000n, cliffTimestamp: 2000n, endTimestamp: 5000n, unlock: { linear: {} } },
+      tokens: [
+        { mint: tokenMintAddress, amount: 1000000n },
+        { mint: usdcMint },
+        { mint: usdtMint },
+      ],
+    });
+    await setClock(3000);
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw more than allocated", async () => {
+    const promise = testTransaction([instruction(1500000n)]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw a token that is not in the allocation", async () => {
+    await setAllocation({
+      nftMint: nftMintAddress,
+    });
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+
+  it("Should not be able to withdraw if not the owner", async () => {
+    await closeAccount(nftTokenAddress);
+    const promise = testTransaction([instruction()]);
+    await assert.rejects(promise);
+  });
+});